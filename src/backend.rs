@@ -0,0 +1,110 @@
+use crate::ffi;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum Backend {
+    /// Any available backend
+    Any,
+    /// Linux kernel driver
+    Linux,
+    /// libusb
+    LibUsb,
+    /// CyAPI
+    Cypress,
+    /// Dummy, used for development
+    Dummy,
+}
+
+impl Backend {
+    pub(crate) fn into_ffi(self) -> ffi::bladerf_backend {
+        use ffi::bladerf_backend::*;
+        use Backend::*;
+        match self {
+            Any => BLADERF_BACKEND_ANY,
+            Linux => BLADERF_BACKEND_LINUX,
+            LibUsb => BLADERF_BACKEND_LIBUSB,
+            Cypress => BLADERF_BACKEND_CYPRESS,
+            Dummy => BLADERF_BACKEND_DUMMY,
+        }
+    }
+
+    pub(crate) fn from_ffi(backend: ffi::bladerf_backend) -> Self {
+        use ffi::bladerf_backend::*;
+        use Backend::*;
+        match backend {
+            BLADERF_BACKEND_ANY => Any,
+            BLADERF_BACKEND_LINUX => Linux,
+            BLADERF_BACKEND_LIBUSB => LibUsb,
+            BLADERF_BACKEND_CYPRESS => Cypress,
+            BLADERF_BACKEND_DUMMY => Dummy,
+        }
+    }
+
+    /// Name used in device identifier strings
+    pub fn as_str(self) -> &'static str {
+        use Backend::*;
+        match self {
+            Any => "*",
+            Linux => "linux",
+            LibUsb => "libusb",
+            Cypress => "cypress",
+            Dummy => "dummy",
+        }
+    }
+
+    pub(crate) fn from_identifier(s: &str) -> Option<Self> {
+        use Backend::*;
+        match s {
+            "" | "*" => Some(Any),
+            "linux" => Some(Linux),
+            "libusb" => Some(LibUsb),
+            "cypress" => Some(Cypress),
+            "dummy" => Some(Dummy),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Backend::*;
+        match self {
+            Any => write!(f, "Any"),
+            Linux => write!(f, "Linux"),
+            LibUsb => write!(f, "libusb"),
+            Cypress => write!(f, "Cypress"),
+            Dummy => write!(f, "Dummy"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backend_ffi() {
+        use ffi::bladerf_backend::*;
+        use Backend::*;
+        assert_eq!(Any.into_ffi(), BLADERF_BACKEND_ANY);
+        assert_eq!(Linux.into_ffi(), BLADERF_BACKEND_LINUX);
+        assert_eq!(LibUsb.into_ffi(), BLADERF_BACKEND_LIBUSB);
+        assert_eq!(Cypress.into_ffi(), BLADERF_BACKEND_CYPRESS);
+        assert_eq!(Dummy.into_ffi(), BLADERF_BACKEND_DUMMY);
+
+        for b in [Any, Linux, LibUsb, Cypress, Dummy] {
+            assert_eq!(Backend::from_ffi(b.into_ffi()), b);
+        }
+    }
+
+    #[test]
+    fn backend_identifier() {
+        use Backend::*;
+        for b in [Any, Linux, LibUsb, Cypress, Dummy] {
+            assert_eq!(Backend::from_identifier(b.as_str()), Some(b));
+        }
+        assert_eq!(Backend::from_identifier(""), Some(Any));
+        assert_eq!(Backend::from_identifier("usb"), None);
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{Channel, Device};
+use std::ops::{Deref, DerefMut};
+
+/// Keeps the bias tee of a channel enabled, disabling it on drop
+///
+/// Dereferences to the `Device` so it can still be used while the guard is held.
+/// Drop also runs when unwinding from a panic, but not on abort.
+#[derive(Debug)]
+pub struct BiasTeeGuard<'a> {
+    dev: &'a mut Device,
+    ch: Channel,
+}
+
+impl<'a> BiasTeeGuard<'a> {
+    pub(crate) fn new(dev: &'a mut Device, ch: Channel) -> Self {
+        BiasTeeGuard { dev, ch }
+    }
+
+    pub fn channel(&self) -> Channel {
+        self.ch
+    }
+}
+
+impl Deref for BiasTeeGuard<'_> {
+    type Target = Device;
+
+    fn deref(&self) -> &Device {
+        self.dev
+    }
+}
+
+impl DerefMut for BiasTeeGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Device {
+        self.dev
+    }
+}
+
+impl Drop for BiasTeeGuard<'_> {
+    fn drop(&mut self) {
+        // Nothing useful can be done with an error here
+        let _ = self.dev.set_bias_tee(self.ch, false);
+    }
+}
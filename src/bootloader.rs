@@ -0,0 +1,106 @@
+use crate::device_info::DeviceList;
+use crate::{ffi, path_to_cstring, Backend, DeviceInfo, Error};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+/// A device waiting in the FX3 bootloader, e.g. after a failed firmware
+/// update or `Device::jump_to_bootloader`
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Bootloader {
+    info: DeviceInfo,
+}
+
+impl Bootloader {
+    /// List the devices in bootloader mode
+    pub fn list() -> Result<Vec<Bootloader>, Error> {
+        let mut devices: *mut ffi::bladerf_devinfo = std::ptr::null_mut();
+        let count = unsafe { ffi::bladerf_get_bootloader_list(&mut devices) };
+        if count == ffi::BLADERF_ERR_NODEV {
+            return Ok(Vec::new());
+        } else if count < 0 {
+            return Err(Error::from(count));
+        }
+        let list = unsafe { DeviceList::from_raw(devices, count as usize) };
+        Ok(list
+            .as_slice()
+            .iter()
+            .copied()
+            .map(|info| Bootloader { info: info.into() })
+            .collect())
+    }
+
+    /// Bootloader devices have no serial number, they are identified by
+    /// backend and USB bus/address
+    pub fn info(&self) -> &DeviceInfo {
+        &self.info
+    }
+
+    pub fn backend(&self) -> Backend {
+        self.info.backend()
+    }
+
+    pub fn usb_bus(&self) -> u8 {
+        self.info.usb_bus()
+    }
+
+    pub fn usb_addr(&self) -> u8 {
+        self.info.usb_addr()
+    }
+
+    /// Download a firmware image to RAM and boot it
+    ///
+    /// The firmware is not written to flash, use `Device::flash_firmware`
+    /// on the booted device to recover it permanently.
+    pub fn load_firmware<P: AsRef<Path>>(&self, image: P) -> Result<(), Error> {
+        let image = path_to_cstring(image.as_ref())?;
+        let err = unsafe {
+            ffi::bladerf_load_fw_from_bootloader(
+                std::ptr::null(),
+                self.backend().into_ffi(),
+                self.usb_bus(),
+                self.usb_addr(),
+                image.as_ptr(),
+            )
+        };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Bootloader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Bootloader, {} USB {}:{}",
+            self.backend(),
+            self.usb_bus(),
+            self.usb_addr()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bootloader_from_devinfo() {
+        let raw = ffi::bladerf_devinfo {
+            backend: ffi::bladerf_backend::BLADERF_BACKEND_CYPRESS,
+            serial: [0; ffi::BLADERF_SERIAL_LENGTH as usize],
+            usb_bus: 3,
+            usb_addr: 9,
+            instance: 0,
+            manufacturer: [0; ffi::BLADERF_DESCRIPTION_LENGTH as usize],
+            product: [0; ffi::BLADERF_DESCRIPTION_LENGTH as usize],
+        };
+        let bl = Bootloader { info: raw.into() };
+        assert_eq!(bl.backend(), Backend::Cypress);
+        assert_eq!((bl.usb_bus(), bl.usb_addr()), (3, 9));
+        assert_eq!(bl.info().serial(), "");
+        assert_eq!(bl.to_string(), "Bootloader, Cypress USB 3:9");
+    }
+}
@@ -0,0 +1,29 @@
+use crate::{Bandwidth, Frequency, Gain, Range, SampleRate};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Supported tuning ranges of a channel, as reported by the device
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Capabilities {
+    pub frequency: Range<Frequency>,
+    pub sample_rate: Range<SampleRate>,
+    pub bandwidth: Range<Bandwidth>,
+    pub gain: Range<Gain>,
+}
+
+impl fmt::Display for Capabilities {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "frequency=[{}, {}], sample-rate=[{}, {}], bandwidth=[{}, {}], gain=[{}, {}]",
+            self.frequency.min,
+            self.frequency.max,
+            self.sample_rate.min,
+            self.sample_rate.max,
+            self.bandwidth.min,
+            self.bandwidth.max,
+            self.gain.min,
+            self.gain.max,
+        )
+    }
+}
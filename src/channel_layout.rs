@@ -23,6 +23,12 @@ impl ChannelLayout {
     }
 }
 
+impl ChannelLayout {
+    pub fn is_tx(self) -> bool {
+        matches!(self, ChannelLayout::TxX1 | ChannelLayout::TxX2)
+    }
+}
+
 impl fmt::Display for ChannelLayout {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use ChannelLayout::*;
@@ -0,0 +1,134 @@
+use crate::ffi;
+use crate::units::Hertz;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Source of the reference clock (bladeRF2)
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum ClockSelect {
+    /// Onboard VCTCXO
+    Onboard,
+    /// External clock input (CLKIN)
+    External,
+}
+
+impl ClockSelect {
+    pub(crate) fn into_ffi(self) -> ffi::bladerf_clock_select {
+        use ffi::bladerf_clock_select::*;
+        use ClockSelect::*;
+        match self {
+            Onboard => CLOCK_SELECT_ONBOARD,
+            External => CLOCK_SELECT_EXTERNAL,
+        }
+    }
+
+    pub(crate) fn from_ffi(sel: ffi::bladerf_clock_select) -> Self {
+        use ffi::bladerf_clock_select::*;
+        use ClockSelect::*;
+        match sel {
+            CLOCK_SELECT_ONBOARD => Onboard,
+            CLOCK_SELECT_EXTERNAL => External,
+        }
+    }
+}
+
+impl fmt::Display for ClockSelect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use ClockSelect::*;
+        match self {
+            Onboard => write!(f, "Onboard"),
+            External => write!(f, "External"),
+        }
+    }
+}
+
+/// Reference clock configuration (bladeRF2)
+///
+/// Read with `Device::clock_config` and applied with
+/// `Device::set_clock_config`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct ClockConfig {
+    pub select: ClockSelect,
+    /// Drive the reference clock on the clock output connector
+    pub output: bool,
+    /// Frequency of the reference (REFIN) the PLL locks to, `None` when the
+    /// PLL is disabled
+    pub pll_refclk: Option<Hertz>,
+}
+
+impl Default for ClockConfig {
+    fn default() -> Self {
+        ClockConfig {
+            select: ClockSelect::Onboard,
+            output: false,
+            pll_refclk: None,
+        }
+    }
+}
+
+impl ClockConfig {
+    /// Discipline the onboard VCTCXO from an external reference via the PLL
+    pub fn pll<T: Into<Hertz>>(refclk: T) -> Self {
+        ClockConfig {
+            pll_refclk: Some(refclk.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_select(mut self, select: ClockSelect) -> Self {
+        self.select = select;
+        self
+    }
+
+    pub fn with_output(mut self, output: bool) -> Self {
+        self.output = output;
+        self
+    }
+}
+
+impl fmt::Display for ClockConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} clock", self.select)?;
+        if self.output {
+            write!(f, ", output enabled")?;
+        }
+        match self.pll_refclk {
+            Some(refclk) => write!(f, ", PLL locked to {}", refclk),
+            None => write!(f, ", PLL disabled"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MegaHertz;
+
+    #[test]
+    fn clock_select_ffi() {
+        use ffi::bladerf_clock_select::*;
+        assert_eq!(ClockSelect::Onboard.into_ffi(), CLOCK_SELECT_ONBOARD);
+        assert_eq!(ClockSelect::External.into_ffi(), CLOCK_SELECT_EXTERNAL);
+        for sel in [ClockSelect::Onboard, ClockSelect::External] {
+            assert_eq!(ClockSelect::from_ffi(sel.into_ffi()), sel);
+        }
+    }
+
+    #[test]
+    fn clock_config() {
+        let config = ClockConfig::pll(MegaHertz(10)).with_output(true);
+        assert_eq!(config.select, ClockSelect::Onboard);
+        assert_eq!(config.pll_refclk, Some(Hertz(10_000_000)));
+        assert_eq!(ClockConfig::default().pll_refclk, None);
+        assert_eq!(
+            ClockConfig::default()
+                .with_select(ClockSelect::External)
+                .to_string(),
+            "External clock, PLL disabled"
+        );
+
+        let json = serde_json::to_string(&config).unwrap();
+        let de: ClockConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(de, config);
+    }
+}
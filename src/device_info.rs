@@ -1,48 +1,120 @@
-use crate::ffi::{bladerf_backend, bladerf_devinfo};
-use crate::Error;
-use std::{ffi::CStr, fmt, str};
+use crate::ffi::{self, bladerf_devinfo};
+use crate::{Backend, Error};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::ffi::CString;
+use std::fmt;
+use std::os::raw::c_char;
 
-#[derive(Copy, Clone)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct DeviceInfo {
-    pub(crate) inner: bladerf_devinfo,
+    backend: Backend,
+    serial: String,
+    usb_bus: u8,
+    usb_addr: u8,
+    instance: u32,
+    manufacturer: String,
+    product: String,
 }
 
 impl DeviceInfo {
-    pub fn backend(&self) -> bladerf_backend {
-        self.inner.backend
+    pub fn backend(&self) -> Backend {
+        self.backend
     }
 
-    pub fn serial(&self) -> Result<&str, Error> {
-        let slice = unsafe { CStr::from_ptr(self.inner.serial.as_ptr()) };
-        slice.to_str().map_err(|_| Error::CString)
+    pub fn serial(&self) -> &str {
+        &self.serial
     }
 
     pub fn usb_bus(&self) -> u8 {
-        self.inner.usb_bus
+        self.usb_bus
     }
 
     pub fn usb_addr(&self) -> u8 {
-        self.inner.usb_addr
+        self.usb_addr
     }
 
     pub fn instance(&self) -> u32 {
-        self.inner.instance
+        self.instance
     }
 
-    pub fn manufacturer(&self) -> Result<&str, Error> {
-        let slice = unsafe { CStr::from_ptr(self.inner.manufacturer.as_ptr()) };
-        slice.to_str().map_err(|_| Error::CString)
+    pub fn manufacturer(&self) -> &str {
+        &self.manufacturer
     }
 
-    pub fn product(&self) -> Result<&str, Error> {
-        let slice = unsafe { CStr::from_ptr(self.inner.product.as_ptr()) };
-        slice.to_str().map_err(|_| Error::CString)
+    pub fn product(&self) -> &str {
+        &self.product
     }
+
+    /// Returns true if the two device descriptions refer to the same device,
+    /// honoring libbladeRF's wildcard fields
+    pub fn matches(&self, other: &DeviceInfo) -> Result<bool, Error> {
+        let a = bladerf_devinfo::try_from(self)?;
+        let b = bladerf_devinfo::try_from(other)?;
+        Ok(unsafe { ffi::bladerf_devinfo_matches(&a, &b) })
+    }
+
+    /// Returns true if this device is matched by the device identifier string
+    pub fn matches_str(&self, device_id: &str) -> Result<bool, Error> {
+        let dev_id_cstr = CString::new(device_id).map_err(|_| Error::CString)?;
+        let mut info = bladerf_devinfo::try_from(self)?;
+        Ok(unsafe { ffi::bladerf_devstr_matches(dev_id_cstr.as_ptr(), &mut info) })
+    }
+}
+
+fn string_from_ffi(chars: &[c_char]) -> String {
+    let bytes: Vec<u8> = chars
+        .iter()
+        .take_while(|c| **c != 0)
+        .map(|c| *c as u8)
+        .collect();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn string_into_ffi(s: &str, chars: &mut [c_char]) -> Result<(), Error> {
+    // Leave room for the nul terminator
+    if s.len() >= chars.len() || s.bytes().any(|b| b == 0) {
+        return Err(Error::CString);
+    }
+    chars.iter_mut().for_each(|c| *c = 0);
+    chars
+        .iter_mut()
+        .zip(s.bytes())
+        .for_each(|(c, b)| *c = b as c_char);
+    Ok(())
 }
 
 impl From<bladerf_devinfo> for DeviceInfo {
     fn from(t: bladerf_devinfo) -> DeviceInfo {
-        DeviceInfo { inner: t }
+        DeviceInfo {
+            backend: Backend::from_ffi(t.backend),
+            serial: string_from_ffi(&t.serial),
+            usb_bus: t.usb_bus,
+            usb_addr: t.usb_addr,
+            instance: t.instance,
+            manufacturer: string_from_ffi(&t.manufacturer),
+            product: string_from_ffi(&t.product),
+        }
+    }
+}
+
+impl TryFrom<&DeviceInfo> for bladerf_devinfo {
+    type Error = Error;
+
+    fn try_from(info: &DeviceInfo) -> Result<Self, Self::Error> {
+        let mut t = bladerf_devinfo {
+            backend: info.backend.into_ffi(),
+            serial: [0; ffi::BLADERF_SERIAL_LENGTH as usize],
+            usb_bus: info.usb_bus,
+            usb_addr: info.usb_addr,
+            instance: info.instance,
+            manufacturer: [0; ffi::BLADERF_DESCRIPTION_LENGTH as usize],
+            product: [0; ffi::BLADERF_DESCRIPTION_LENGTH as usize],
+        };
+        string_into_ffi(&info.serial, &mut t.serial)?;
+        string_into_ffi(&info.manufacturer, &mut t.manufacturer)?;
+        string_into_ffi(&info.product, &mut t.product)?;
+        Ok(t)
     }
 }
 
@@ -51,10 +123,80 @@ impl fmt::Display for DeviceInfo {
         write!(
             f,
             "{}, USB {}:{}, serial={}",
-            self.product().unwrap_or("NA"),
+            self.product(),
             self.usb_bus(),
             self.usb_addr(),
-            self.serial().unwrap_or("NA")
+            self.serial()
         )
     }
 }
+
+/// Device list allocated by libbladeRF, freed on drop
+pub(crate) struct DeviceList {
+    devices: *mut bladerf_devinfo,
+    len: usize,
+}
+
+impl DeviceList {
+    /// Takes ownership of a list returned by `bladerf_get_device_list`
+    /// or `bladerf_get_bootloader_list`
+    pub(crate) unsafe fn from_raw(devices: *mut bladerf_devinfo, len: usize) -> Self {
+        DeviceList { devices, len }
+    }
+
+    pub(crate) fn as_slice(&self) -> &[bladerf_devinfo] {
+        if self.devices.is_null() {
+            &[]
+        } else {
+            unsafe { std::slice::from_raw_parts(self.devices, self.len) }
+        }
+    }
+}
+
+impl Drop for DeviceList {
+    fn drop(&mut self) {
+        if !self.devices.is_null() {
+            unsafe { ffi::bladerf_free_device_list(self.devices) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    fn devinfo() -> DeviceInfo {
+        DeviceInfo {
+            backend: Backend::LibUsb,
+            serial: "f12ce1037830a1b27f3ceeba1f521413".to_owned(),
+            usb_bus: 2,
+            usb_addr: 7,
+            instance: 0,
+            manufacturer: "Nuand".to_owned(),
+            product: "bladeRF 2.0".to_owned(),
+        }
+    }
+
+    #[test]
+    fn devinfo_ffi_round_trip() {
+        let info = devinfo();
+        let raw = bladerf_devinfo::try_from(&info).unwrap();
+        assert_eq!(raw.backend, ffi::bladerf_backend::BLADERF_BACKEND_LIBUSB);
+        assert_eq!(raw.usb_bus, 2);
+        assert_eq!(raw.usb_addr, 7);
+        let serial = unsafe { CStr::from_ptr(raw.serial.as_ptr()) };
+        assert_eq!(serial.to_str(), Ok(info.serial()));
+        assert_eq!(DeviceInfo::from(raw), info);
+    }
+
+    #[test]
+    fn devinfo_ffi_too_long() {
+        let mut info = devinfo();
+        info.serial = "0".repeat(ffi::BLADERF_SERIAL_LENGTH as usize);
+        assert_eq!(bladerf_devinfo::try_from(&info).err(), Some(Error::CString));
+        let mut info = devinfo();
+        info.product = "a\0b".to_owned();
+        assert_eq!(bladerf_devinfo::try_from(&info).err(), Some(Error::CString));
+    }
+}
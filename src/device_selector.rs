@@ -0,0 +1,298 @@
+use crate::Backend;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+/// Typed libbladeRF device identifier
+///
+/// Follows the `<backend>:[device=<bus>:<addr>] [instance=<n>] [serial=<serial>]`
+/// grammar accepted by `bladerf_open`, e.g. `*:serial=f12ce1037830a1b2`.
+/// The serial may be a prefix of the full serial number and is validated
+/// when set, so the `Display` output always parses back.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct DeviceSelector {
+    backend: Backend,
+    /// USB bus and address
+    usb: Option<(u8, u8)>,
+    instance: Option<u32>,
+    serial: Option<String>,
+}
+
+impl Default for DeviceSelector {
+    fn default() -> Self {
+        DeviceSelector::any()
+    }
+}
+
+impl DeviceSelector {
+    /// Matches the first available device
+    pub fn any() -> Self {
+        DeviceSelector {
+            backend: Backend::Any,
+            usb: None,
+            instance: None,
+            serial: None,
+        }
+    }
+
+    pub fn with_backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    pub fn with_usb(mut self, bus: u8, addr: u8) -> Self {
+        self.usb = Some((bus, addr));
+        self
+    }
+
+    pub fn with_instance(mut self, instance: u32) -> Self {
+        self.instance = Some(instance);
+        self
+    }
+
+    /// Fails unless `serial` is a non-empty hex string no longer than
+    /// a full serial number
+    pub fn with_serial<S: Into<String>>(
+        mut self,
+        serial: S,
+    ) -> Result<Self, ParseDeviceSelectorError> {
+        let serial = serial.into();
+        if !is_valid_serial(&serial) {
+            return Err(ParseDeviceSelectorError::Serial(serial));
+        }
+        self.serial = Some(serial);
+        Ok(self)
+    }
+
+    pub fn backend(&self) -> Backend {
+        self.backend
+    }
+
+    pub fn usb(&self) -> Option<(u8, u8)> {
+        self.usb
+    }
+
+    pub fn instance(&self) -> Option<u32> {
+        self.instance
+    }
+
+    pub fn serial(&self) -> Option<&str> {
+        self.serial.as_deref()
+    }
+}
+
+fn is_valid_serial(serial: &str) -> bool {
+    !serial.is_empty()
+        && serial.len() < crate::ffi::BLADERF_SERIAL_LENGTH as usize
+        && serial.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+impl fmt::Display for DeviceSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.backend.as_str())?;
+        let mut sep = "";
+        if let Some((bus, addr)) = self.usb {
+            write!(f, "{}device={}:{}", sep, bus, addr)?;
+            sep = " ";
+        }
+        if let Some(instance) = self.instance {
+            write!(f, "{}instance={}", sep, instance)?;
+            sep = " ";
+        }
+        if let Some(serial) = &self.serial {
+            write!(f, "{}serial={}", sep, serial)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ParseDeviceSelectorError {
+    #[error("Unknown backend '{0}'")]
+    Backend(String),
+    #[error("Invalid option '{0}'")]
+    Option(String),
+    #[error("Duplicate option '{0}'")]
+    Duplicate(String),
+    #[error("Invalid USB device '{0}', expected <bus>:<addr>")]
+    Usb(String),
+    #[error("Invalid serial '{0}'")]
+    Serial(String),
+    #[error("Parse error. {0}")]
+    IntError(String),
+}
+
+impl From<ParseIntError> for ParseDeviceSelectorError {
+    fn from(e: ParseIntError) -> Self {
+        ParseDeviceSelectorError::IntError(e.to_string())
+    }
+}
+
+impl FromStr for DeviceSelector {
+    type Err = ParseDeviceSelectorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use ParseDeviceSelectorError as E;
+        let s = s.trim();
+        let (backend, options) = s.split_once(':').unwrap_or((s, ""));
+        let backend = Backend::from_identifier(backend.trim())
+            .ok_or_else(|| E::Backend(backend.to_owned()))?;
+        let mut sel = DeviceSelector::any().with_backend(backend);
+        for opt in options.split_whitespace() {
+            let (key, val) = opt
+                .split_once('=')
+                .ok_or_else(|| E::Option(opt.to_owned()))?;
+            match key {
+                "device" => {
+                    if sel.usb.is_some() {
+                        return Err(E::Duplicate(key.to_owned()));
+                    }
+                    let (bus, addr) = val.split_once(':').ok_or_else(|| E::Usb(val.to_owned()))?;
+                    sel.usb = Some((bus.parse()?, addr.parse()?));
+                }
+                "instance" => {
+                    if sel.instance.is_some() {
+                        return Err(E::Duplicate(key.to_owned()));
+                    }
+                    sel.instance = Some(val.parse()?);
+                }
+                "serial" => {
+                    if sel.serial.is_some() {
+                        return Err(E::Duplicate(key.to_owned()));
+                    }
+                    sel = sel.with_serial(val)?;
+                }
+                _ => return Err(E::Option(opt.to_owned())),
+            }
+        }
+        Ok(sel)
+    }
+}
+
+impl Serialize for DeviceSelector {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DeviceSelector {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        DeviceSelector::from_str(&s).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selector_display() {
+        assert_eq!(DeviceSelector::any().to_string(), "*:");
+        assert_eq!(
+            DeviceSelector::any()
+                .with_serial("f12ce1037830a1b27f3ceeba1f521413")
+                .unwrap()
+                .to_string(),
+            "*:serial=f12ce1037830a1b27f3ceeba1f521413"
+        );
+        assert_eq!(
+            DeviceSelector::any()
+                .with_backend(Backend::LibUsb)
+                .with_usb(2, 5)
+                .with_instance(1)
+                .with_serial("abc")
+                .unwrap()
+                .to_string(),
+            "libusb:device=2:5 instance=1 serial=abc"
+        );
+    }
+
+    #[test]
+    fn selector_from_str() {
+        assert_eq!(DeviceSelector::from_str(""), Ok(DeviceSelector::any()));
+        assert_eq!(DeviceSelector::from_str("*"), Ok(DeviceSelector::any()));
+        assert_eq!(
+            DeviceSelector::from_str("cypress:instance=3"),
+            Ok(DeviceSelector::any()
+                .with_backend(Backend::Cypress)
+                .with_instance(3))
+        );
+        assert_eq!(
+            DeviceSelector::from_str("*:serial=ABC123  device=1:12"),
+            Ok(DeviceSelector::any()
+                .with_usb(1, 12)
+                .with_serial("ABC123")
+                .unwrap())
+        );
+        assert!(matches!(
+            DeviceSelector::from_str("usb3:"),
+            Err(ParseDeviceSelectorError::Backend(_))
+        ));
+        assert!(matches!(
+            DeviceSelector::from_str("*:foo=1"),
+            Err(ParseDeviceSelectorError::Option(_))
+        ));
+        assert!(matches!(
+            DeviceSelector::from_str("*:device=1"),
+            Err(ParseDeviceSelectorError::Usb(_))
+        ));
+        assert!(matches!(
+            DeviceSelector::from_str("*:device=1:300"),
+            Err(ParseDeviceSelectorError::IntError(_))
+        ));
+        assert!(matches!(
+            DeviceSelector::from_str("*:serial=xyz"),
+            Err(ParseDeviceSelectorError::Serial(_))
+        ));
+        assert!(matches!(
+            DeviceSelector::from_str("*:instance=1 instance=2"),
+            Err(ParseDeviceSelectorError::Duplicate(_))
+        ));
+    }
+
+    #[test]
+    fn selector_round_trip() {
+        let selectors = [
+            DeviceSelector::any(),
+            DeviceSelector::any().with_serial("f12ce103").unwrap(),
+            DeviceSelector::any()
+                .with_backend(Backend::Linux)
+                .with_usb(255, 0),
+            DeviceSelector::any()
+                .with_backend(Backend::Dummy)
+                .with_usb(1, 2)
+                .with_instance(7)
+                .with_serial("0123456789abcdef0123456789abcdef")
+                .unwrap(),
+        ];
+        for sel in selectors {
+            assert_eq!(DeviceSelector::from_str(&sel.to_string()), Ok(sel.clone()));
+
+            let json = serde_json::to_string(&sel).unwrap();
+            assert_eq!(json, format!("\"{}\"", sel));
+            let de: DeviceSelector = serde_json::from_str(&json).unwrap();
+            assert_eq!(de, sel);
+        }
+        assert!(serde_json::from_str::<DeviceSelector>("\"bogus:\"").is_err());
+    }
+
+    #[test]
+    fn selector_serial_validated() {
+        for serial in ["", "xyz", "abc instance=3", &"0".repeat(33)] {
+            assert_eq!(
+                DeviceSelector::any().with_serial(serial),
+                Err(ParseDeviceSelectorError::Serial(serial.to_owned()))
+            );
+        }
+        let sel = DeviceSelector::any()
+            .with_instance(2)
+            .with_serial("f12ce103")
+            .unwrap();
+        assert_eq!(sel.serial(), Some("f12ce103"));
+        assert_eq!(sel.instance(), Some(2));
+        assert_eq!(sel.usb(), None);
+        assert_eq!(sel.backend(), Backend::Any);
+    }
+}
@@ -0,0 +1,42 @@
+use crate::ffi;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum Direction {
+    Rx,
+    Tx,
+}
+
+impl Direction {
+    pub(crate) fn into_ffi(self) -> ffi::bladerf_direction {
+        use ffi::bladerf_direction::*;
+        use Direction::*;
+        match self {
+            Rx => BLADERF_RX,
+            Tx => BLADERF_TX,
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Direction::*;
+        match self {
+            Rx => write!(f, "RX"),
+            Tx => write!(f, "TX"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn direction_ffi() {
+        use ffi::bladerf_direction::*;
+        assert_eq!(Direction::Rx.into_ffi(), BLADERF_RX);
+        assert_eq!(Direction::Tx.into_ffi(), BLADERF_TX);
+    }
+}
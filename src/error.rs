@@ -1,3 +1,4 @@
+use crate::MetadataEvent;
 use std::fmt;
 
 #[non_exhaustive]
@@ -6,6 +7,8 @@ pub enum Error {
     CString,
     SamplesPerBuffer,
     SamplesLen,
+    SampleFormat,
+    FpgaImageSize,
     Unexpected,
     Range,
     Invalid,
@@ -17,6 +20,7 @@ pub enum Error {
     QueueFull,
     WouldBlock,
     DeviceNotInit,
+    Metadata(MetadataEvent),
     Other(i32),
 }
 
@@ -6,8 +6,15 @@ use std::fmt;
 pub enum Format {
     Sc16Q11,
     Sc16Q11Meta,
+    /// Raw packets with metadata, sample counts are in 32-bit words
+    PacketMeta,
+    Sc8Q7,
+    Sc8Q7Meta,
 }
 
+/// Sample buffers must be a multiple of the bladeRF GPIF DMA transfer size
+const DMA_TRANSFER_BYTES: usize = 4096;
+
 impl Format {
     pub(crate) fn into_ffi(self) -> ffi::bladerf_format {
         use ffi::bladerf_format::*;
@@ -15,8 +22,31 @@ impl Format {
         match self {
             Sc16Q11 => BLADERF_FORMAT_SC16_Q11,
             Sc16Q11Meta => BLADERF_FORMAT_SC16_Q11_META,
+            PacketMeta => BLADERF_FORMAT_PACKET_META,
+            Sc8Q7 => BLADERF_FORMAT_SC8_Q7,
+            Sc8Q7Meta => BLADERF_FORMAT_SC8_Q7_META,
+        }
+    }
+
+    /// Size of a sample in bytes (of a 32-bit word for `PacketMeta`)
+    pub fn bytes_per_sample(self) -> usize {
+        use Format::*;
+        match self {
+            Sc16Q11 | Sc16Q11Meta | PacketMeta => 4,
+            Sc8Q7 | Sc8Q7Meta => 2,
         }
     }
+
+    /// Buffer sizes, in samples, must be a multiple of this value
+    pub fn samples_per_buffer_multiple(self) -> usize {
+        DMA_TRANSFER_BYTES / self.bytes_per_sample()
+    }
+
+    /// Returns true if the sync interface requires `Metadata` with this format
+    pub fn has_metadata(self) -> bool {
+        use Format::*;
+        matches!(self, Sc16Q11Meta | PacketMeta | Sc8Q7Meta)
+    }
 }
 
 impl fmt::Display for Format {
@@ -25,6 +55,9 @@ impl fmt::Display for Format {
         match self {
             Sc16Q11 => write!(f, "Signed, Complex 16-bit Q11"),
             Sc16Q11Meta => write!(f, "Signed, Complex 16-bit Q11, with Metadata"),
+            PacketMeta => write!(f, "Packet, with Metadata"),
+            Sc8Q7 => write!(f, "Signed, Complex 8-bit Q7"),
+            Sc8Q7Meta => write!(f, "Signed, Complex 8-bit Q7, with Metadata"),
         }
     }
 }
@@ -39,5 +72,23 @@ mod tests {
         use Format::*;
         assert_eq!(Sc16Q11.into_ffi(), BLADERF_FORMAT_SC16_Q11);
         assert_eq!(Sc16Q11Meta.into_ffi(), BLADERF_FORMAT_SC16_Q11_META);
+        assert_eq!(PacketMeta.into_ffi(), BLADERF_FORMAT_PACKET_META);
+        assert_eq!(Sc8Q7.into_ffi(), BLADERF_FORMAT_SC8_Q7);
+        assert_eq!(Sc8Q7Meta.into_ffi(), BLADERF_FORMAT_SC8_Q7_META);
+    }
+
+    #[test]
+    fn format_sample_size() {
+        use Format::*;
+        assert_eq!(
+            Sc16Q11.samples_per_buffer_multiple(),
+            crate::SAMPLES_PER_BUFFER
+        );
+        assert_eq!(Sc16Q11Meta.samples_per_buffer_multiple(), 1024);
+        assert_eq!(PacketMeta.samples_per_buffer_multiple(), 1024);
+        assert_eq!(Sc8Q7.bytes_per_sample(), 2);
+        assert_eq!(Sc8Q7Meta.samples_per_buffer_multiple(), 2048);
+        assert!(!Sc8Q7.has_metadata());
+        assert!(Sc8Q7Meta.has_metadata());
     }
 }
@@ -0,0 +1,159 @@
+use crate::{ffi, Error};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// FPGA device variant
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum FpgaSize {
+    Unknown,
+    /// 40 kLE (bladeRF1 x40)
+    Kle40,
+    /// 115 kLE (bladeRF1 x115)
+    Kle115,
+    /// 49 kLE (bladeRF2 xA4)
+    A4,
+    /// 77 kLE (bladeRF2 xA5)
+    A5,
+    /// 301 kLE (bladeRF2 xA9)
+    A9,
+}
+
+impl FpgaSize {
+    pub(crate) fn from_ffi(size: ffi::bladerf_fpga_size) -> Self {
+        use ffi::bladerf_fpga_size::*;
+        use FpgaSize::*;
+        match size {
+            BLADERF_FPGA_UNKNOWN => Unknown,
+            BLADERF_FPGA_40KLE => Kle40,
+            BLADERF_FPGA_115KLE => Kle115,
+            BLADERF_FPGA_A4 => A4,
+            BLADERF_FPGA_A5 => A5,
+            BLADERF_FPGA_A9 => A9,
+        }
+    }
+}
+
+impl fmt::Display for FpgaSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use FpgaSize::*;
+        match self {
+            Unknown => write!(f, "Unknown"),
+            Kle40 => write!(f, "40 kLE"),
+            Kle115 => write!(f, "115 kLE"),
+            A4 => write!(f, "A4 (49 kLE)"),
+            A5 => write!(f, "A5 (77 kLE)"),
+            A9 => write!(f, "A9 (301 kLE)"),
+        }
+    }
+}
+
+/// Where the currently loaded FPGA image came from
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum FpgaSource {
+    Unknown,
+    /// Autoloaded from flash on power up
+    Flash,
+    /// Loaded by the host
+    Host,
+}
+
+impl FpgaSource {
+    pub(crate) fn from_ffi(source: ffi::bladerf_fpga_source) -> Self {
+        use ffi::bladerf_fpga_source::*;
+        use FpgaSource::*;
+        match source {
+            BLADERF_FPGA_SOURCE_UNKNOWN => Unknown,
+            BLADERF_FPGA_SOURCE_FLASH => Flash,
+            BLADERF_FPGA_SOURCE_HOST => Host,
+        }
+    }
+}
+
+impl fmt::Display for FpgaSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use FpgaSource::*;
+        match self {
+            Unknown => write!(f, "Unknown"),
+            Flash => write!(f, "Flash"),
+            Host => write!(f, "Host"),
+        }
+    }
+}
+
+/// Pre-flight check of a bitstream size against the board's FPGA size
+///
+/// `fpga_bytes` is the bitstream size reported by `Device::fpga_bytes`, the
+/// image must match it exactly as libbladeRF does not support compressed
+/// bitstreams. When the FPGA size is unknown (`fpga_bytes == 0`) only empty
+/// images are rejected and libbladeRF is left to validate the rest.
+pub fn check_fpga_image_size(image_bytes: u64, fpga_bytes: usize) -> Result<(), Error> {
+    let valid = if fpga_bytes == 0 {
+        image_bytes != 0
+    } else {
+        image_bytes == fpga_bytes as u64
+    };
+    if !valid {
+        return Err(Error::FpgaImageSize);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fpga_size_ffi() {
+        use ffi::bladerf_fpga_size::*;
+        assert_eq!(FpgaSize::from_ffi(BLADERF_FPGA_UNKNOWN), FpgaSize::Unknown);
+        assert_eq!(FpgaSize::from_ffi(BLADERF_FPGA_40KLE), FpgaSize::Kle40);
+        assert_eq!(FpgaSize::from_ffi(BLADERF_FPGA_115KLE), FpgaSize::Kle115);
+        assert_eq!(FpgaSize::from_ffi(BLADERF_FPGA_A4), FpgaSize::A4);
+        assert_eq!(FpgaSize::from_ffi(BLADERF_FPGA_A5), FpgaSize::A5);
+        assert_eq!(FpgaSize::from_ffi(BLADERF_FPGA_A9), FpgaSize::A9);
+    }
+
+    #[test]
+    fn fpga_source_ffi() {
+        use ffi::bladerf_fpga_source::*;
+        assert_eq!(
+            FpgaSource::from_ffi(BLADERF_FPGA_SOURCE_UNKNOWN),
+            FpgaSource::Unknown
+        );
+        assert_eq!(
+            FpgaSource::from_ffi(BLADERF_FPGA_SOURCE_FLASH),
+            FpgaSource::Flash
+        );
+        assert_eq!(
+            FpgaSource::from_ffi(BLADERF_FPGA_SOURCE_HOST),
+            FpgaSource::Host
+        );
+    }
+
+    #[test]
+    fn fpga_image_size() {
+        // xA4 bitstream
+        let fpga_bytes = 2_632_660;
+        assert_eq!(check_fpga_image_size(2_632_660, fpga_bytes), Ok(()));
+        assert_eq!(
+            check_fpga_image_size(1_800_000, fpga_bytes),
+            Err(Error::FpgaImageSize)
+        );
+        assert_eq!(
+            check_fpga_image_size(0, fpga_bytes),
+            Err(Error::FpgaImageSize)
+        );
+        // xA9 bitstream on an xA4 and the other way around
+        assert_eq!(
+            check_fpga_image_size(12_858_972, fpga_bytes),
+            Err(Error::FpgaImageSize)
+        );
+        assert_eq!(
+            check_fpga_image_size(2_632_660, 12_858_972),
+            Err(Error::FpgaImageSize)
+        );
+        // Unknown FPGA size
+        assert_eq!(check_fpga_image_size(2_632_660, 0), Ok(()));
+        assert_eq!(check_fpga_image_size(0, 0), Err(Error::FpgaImageSize));
+    }
+}
@@ -0,0 +1,76 @@
+use crate::ffi;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum GainMode {
+    /// Device-specific default (automatic, when available)
+    Default,
+    /// Manual gain control
+    Manual,
+    /// Fast attack AGC
+    FastAttack,
+    /// Slow attack AGC
+    SlowAttack,
+    /// Hybrid AGC
+    Hybrid,
+}
+
+impl GainMode {
+    pub(crate) fn into_ffi(self) -> ffi::bladerf_gain_mode {
+        use ffi::bladerf_gain_mode::*;
+        use GainMode::*;
+        match self {
+            Default => BLADERF_GAIN_DEFAULT,
+            Manual => BLADERF_GAIN_MGC,
+            FastAttack => BLADERF_GAIN_FASTATTACK_AGC,
+            SlowAttack => BLADERF_GAIN_SLOWATTACK_AGC,
+            Hybrid => BLADERF_GAIN_HYBRID_AGC,
+        }
+    }
+
+    pub(crate) fn from_ffi(mode: ffi::bladerf_gain_mode) -> Self {
+        use ffi::bladerf_gain_mode::*;
+        use GainMode::*;
+        match mode {
+            BLADERF_GAIN_DEFAULT => Default,
+            BLADERF_GAIN_MGC => Manual,
+            BLADERF_GAIN_FASTATTACK_AGC => FastAttack,
+            BLADERF_GAIN_SLOWATTACK_AGC => SlowAttack,
+            BLADERF_GAIN_HYBRID_AGC => Hybrid,
+        }
+    }
+}
+
+impl fmt::Display for GainMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use GainMode::*;
+        match self {
+            Default => write!(f, "Default"),
+            Manual => write!(f, "Manual"),
+            FastAttack => write!(f, "Fast attack AGC"),
+            SlowAttack => write!(f, "Slow attack AGC"),
+            Hybrid => write!(f, "Hybrid AGC"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gain_mode_ffi() {
+        use ffi::bladerf_gain_mode::*;
+        use GainMode::*;
+        assert_eq!(Default.into_ffi(), BLADERF_GAIN_DEFAULT);
+        assert_eq!(Manual.into_ffi(), BLADERF_GAIN_MGC);
+        assert_eq!(FastAttack.into_ffi(), BLADERF_GAIN_FASTATTACK_AGC);
+        assert_eq!(SlowAttack.into_ffi(), BLADERF_GAIN_SLOWATTACK_AGC);
+        assert_eq!(Hybrid.into_ffi(), BLADERF_GAIN_HYBRID_AGC);
+
+        for mode in [Default, Manual, FastAttack, SlowAttack, Hybrid] {
+            assert_eq!(GainMode::from_ffi(mode.into_ffi()), mode);
+        }
+    }
+}
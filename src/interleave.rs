@@ -0,0 +1,204 @@
+//! Conversion between the interleaved MIMO (x2) sample layout used on the
+//! wire, `[ch0[0], ch1[0], ch0[1], ch1[1], ...]`, and per-channel buffers
+
+use crate::{ffi, ChannelLayout, Error, Format, Sample};
+use std::convert::TryInto;
+
+const NUM_CHANNELS: usize = 2;
+
+/// Interleave a buffer in place using libbladeRF
+///
+/// The buffer must hold all samples of channel 0 followed by all samples
+/// of channel 1.
+pub fn interleave_stream_buffer<S: Sample>(
+    layout: ChannelLayout,
+    format: Format,
+    samples: &mut [S],
+) -> Result<(), Error> {
+    if !S::is_compatible(format) {
+        return Err(Error::SampleFormat);
+    }
+    let err = unsafe {
+        ffi::bladerf_interleave_stream_buffer(
+            layout.into_ffi(),
+            format.into_ffi(),
+            samples.len().try_into().map_err(|_| Error::Range)?,
+            samples.as_mut_ptr() as *mut _,
+        )
+    };
+    if err != 0 {
+        return Err(Error::from(err));
+    }
+    Ok(())
+}
+
+/// Deinterleave a buffer in place using libbladeRF
+///
+/// Afterwards the buffer holds all samples of channel 0 followed by all
+/// samples of channel 1.
+pub fn deinterleave_stream_buffer<S: Sample>(
+    layout: ChannelLayout,
+    format: Format,
+    samples: &mut [S],
+) -> Result<(), Error> {
+    if !S::is_compatible(format) {
+        return Err(Error::SampleFormat);
+    }
+    let err = unsafe {
+        ffi::bladerf_deinterleave_stream_buffer(
+            layout.into_ffi(),
+            format.into_ffi(),
+            samples.len().try_into().map_err(|_| Error::Range)?,
+            samples.as_mut_ptr() as *mut _,
+        )
+    };
+    if err != 0 {
+        return Err(Error::from(err));
+    }
+    Ok(())
+}
+
+/// Split an interleaved x2 buffer into one buffer per channel
+///
+/// Each channel buffer must hold exactly half of the interleaved samples.
+pub fn deinterleave_into<S: Sample>(
+    interleaved: &[S],
+    channels: [&mut [S]; NUM_CHANNELS],
+) -> Result<(), Error> {
+    let [ch0, ch1] = channels;
+    let len = interleaved.len() / NUM_CHANNELS;
+    if interleaved.len() % NUM_CHANNELS != 0 || ch0.len() != len || ch1.len() != len {
+        return Err(Error::SamplesLen);
+    }
+    for ((pair, s0), s1) in interleaved
+        .chunks_exact(NUM_CHANNELS)
+        .zip(ch0.iter_mut())
+        .zip(ch1.iter_mut())
+    {
+        *s0 = pair[0];
+        *s1 = pair[1];
+    }
+    Ok(())
+}
+
+/// Merge one buffer per channel into an interleaved x2 buffer
+///
+/// The interleaved buffer must hold exactly twice the samples of a channel.
+pub fn interleave_from<S: Sample>(
+    channels: [&[S]; NUM_CHANNELS],
+    interleaved: &mut [S],
+) -> Result<(), Error> {
+    let [ch0, ch1] = channels;
+    if ch0.len() != ch1.len() || interleaved.len() != ch0.len() * NUM_CHANNELS {
+        return Err(Error::SamplesLen);
+    }
+    for ((pair, s0), s1) in interleaved
+        .chunks_exact_mut(NUM_CHANNELS)
+        .zip(ch0.iter())
+        .zip(ch1.iter())
+    {
+        pair[0] = *s0;
+        pair[1] = *s1;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Sc16Q11, Sc8Q7};
+
+    fn interleaved() -> Vec<Sc16Q11> {
+        // ch0 samples have positive I, ch1 samples negative I
+        (0..8)
+            .map(|n| {
+                let i = (n / 2) as i16 + 1;
+                if n % 2 == 0 {
+                    Sc16Q11::new(i, 100 + i)
+                } else {
+                    Sc16Q11::new(-i, -100 - i)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn deinterleave_matches_c_layout() {
+        let buf = interleaved();
+        let mut ch0 = [Sc16Q11::default(); 4];
+        let mut ch1 = [Sc16Q11::default(); 4];
+        deinterleave_into(&buf, [&mut ch0, &mut ch1]).unwrap();
+        assert_eq!(ch0[0], Sc16Q11::new(1, 101));
+        assert_eq!(ch0[3], Sc16Q11::new(4, 104));
+        assert_eq!(ch1[0], Sc16Q11::new(-1, -101));
+        assert_eq!(ch1[3], Sc16Q11::new(-4, -104));
+
+        let mut c_buf = buf.clone();
+        deinterleave_stream_buffer(ChannelLayout::RxX2, Format::Sc16Q11, &mut c_buf).unwrap();
+        assert_eq!(&c_buf[..4], &ch0);
+        assert_eq!(&c_buf[4..], &ch1);
+    }
+
+    #[test]
+    fn interleave_matches_c_layout() {
+        let ch0 = [Sc8Q7::new(1, 2), Sc8Q7::new(3, 4), Sc8Q7::new(5, 6)];
+        let ch1 = [Sc8Q7::new(-1, -2), Sc8Q7::new(-3, -4), Sc8Q7::new(-5, -6)];
+        let mut out = [Sc8Q7::default(); 6];
+        interleave_from([&ch0, &ch1], &mut out).unwrap();
+
+        let mut c_buf = [ch0, ch1].concat();
+        interleave_stream_buffer(ChannelLayout::TxX2, Format::Sc8Q7, &mut c_buf).unwrap();
+        assert_eq!(c_buf, out);
+    }
+
+    #[test]
+    fn interleave_round_trip() {
+        let buf = interleaved();
+        let mut ch0 = [Sc16Q11::default(); 4];
+        let mut ch1 = [Sc16Q11::default(); 4];
+        deinterleave_into(&buf, [&mut ch0, &mut ch1]).unwrap();
+        let mut out = vec![Sc16Q11::default(); 8];
+        interleave_from([&ch0, &ch1], &mut out).unwrap();
+        assert_eq!(out, buf);
+
+        let ch0 = [Sc8Q7::new(1, 2), Sc8Q7::new(3, 4)];
+        let ch1 = [Sc8Q7::new(-1, -2), Sc8Q7::new(-3, -4)];
+        let mut out = [Sc8Q7::default(); 4];
+        interleave_from([&ch0, &ch1], &mut out).unwrap();
+        assert_eq!(out, [ch0[0], ch1[0], ch0[1], ch1[1]]);
+    }
+
+    #[test]
+    fn interleave_lengths() {
+        let buf = interleaved();
+        let mut ch0 = [Sc16Q11::default(); 4];
+        let mut ch1 = [Sc16Q11::default(); 3];
+        assert_eq!(
+            deinterleave_into(&buf, [&mut ch0, &mut ch1]),
+            Err(Error::SamplesLen)
+        );
+        let mut ch1 = [Sc16Q11::default(); 4];
+        assert_eq!(
+            deinterleave_into(&buf[..7], [&mut ch0, &mut ch1]),
+            Err(Error::SamplesLen)
+        );
+        let mut out = [Sc16Q11::default(); 7];
+        assert_eq!(
+            interleave_from([&ch0, &ch1], &mut out),
+            Err(Error::SamplesLen)
+        );
+    }
+
+    #[test]
+    fn stream_buffer_format_mismatch() {
+        let mut buf = [Sc8Q7::default(); 4];
+        assert_eq!(
+            deinterleave_stream_buffer(ChannelLayout::RxX2, Format::Sc16Q11, &mut buf),
+            Err(Error::SampleFormat)
+        );
+        assert_eq!(
+            interleave_stream_buffer(ChannelLayout::TxX2, Format::Sc16Q11, &mut buf),
+            Err(Error::SampleFormat)
+        );
+    }
+}
@@ -10,32 +10,84 @@
 // - check/fix the CString stuff
 // - use utils in https://crates.io/crates/hertz
 
-use std::convert::TryInto;
+// `usize::is_multiple_of` needs Rust 1.87, keep the `%` checks so the crate
+// does not require it
+#![allow(clippy::manual_is_multiple_of)]
+
+use std::convert::{TryFrom, TryInto};
 use std::ffi::{CStr, CString};
 use std::mem::MaybeUninit;
+use std::path::Path;
+use std::time::{Duration, Instant};
 
+mod backend;
+mod bias_tee;
+mod bootloader;
+mod capabilities;
 mod channel;
 mod channel_layout;
+mod clock;
 mod device_info;
+mod device_selector;
+mod direction;
 mod error;
 pub mod ffi;
 mod format;
+mod fpga;
+mod gain_mode;
+mod interleave;
+mod loopback;
 mod metadata;
+mod quick_tune;
+mod range;
+mod rf_port;
+mod rx_mux;
+mod sample;
+mod stream;
+mod trigger;
+mod tuning_mode;
 pub mod units;
+mod vctcxo;
+mod version;
 
+pub use backend::Backend;
+pub use bias_tee::BiasTeeGuard;
+pub use bootloader::Bootloader;
+pub use capabilities::Capabilities;
 pub use channel::Channel;
 pub use channel_layout::ChannelLayout;
+pub use clock::{ClockConfig, ClockSelect};
 pub use device_info::DeviceInfo;
+use device_info::DeviceList;
+pub use device_selector::{DeviceSelector, ParseDeviceSelectorError};
+pub use direction::Direction;
 pub use error::Error;
 pub use format::Format;
-pub use metadata::{MetaFlags, MetaStatus, Metadata};
-pub use units::{Hertz, KiloHertz, MegaHertz, MilliSeconds, Sps, UnitExt};
+pub use fpga::{check_fpga_image_size, FpgaSize, FpgaSource};
+pub use gain_mode::GainMode;
+pub use interleave::{
+    deinterleave_into, deinterleave_stream_buffer, interleave_from, interleave_stream_buffer,
+};
+pub use loopback::Loopback;
+pub use metadata::{MetaFlags, MetaStatus, Metadata, MetadataEvent};
+pub use quick_tune::QuickTune;
+pub use range::{Range, RangeUnit};
+pub use rf_port::RfPort;
+pub use rx_mux::{check_counter32, CounterGap, RxMux};
+pub use sample::{Sample, Sc16Q11, Sc8Q7};
+pub use stream::{Stream, StreamAction};
+pub use trigger::{Trigger, TriggerRole, TriggerSignal, TriggerState};
+pub use tuning_mode::TuningMode;
+pub use units::{Gain, Hertz, KiloHertz, MegaHertz, MilliSeconds, Sps, Timestamp, UnitExt};
+pub use vctcxo::{trim_correction, TamerMode, Vctcxo};
+pub use version::{lib_version, min_versions, MinVersions, Version, VersionWarning};
 
 pub type Frequency = Hertz;
 pub type Bandwidth = Hertz;
 pub type SampleRate = Sps;
 
 // BladeRF 2.0 micro xA4 limits
+#[deprecated(note = "board specific, use Device::capabilities instead")]
 pub mod device_limits {
     use super::{Hertz, Sps};
     pub const FREQUENCY_MIN: Hertz = Hertz(70_000_000);
@@ -47,11 +99,15 @@ pub mod device_limits {
 }
 
 pub const SAMPLES_PER_BUFFER: usize = 1024;
+#[deprecated(note = "sample size depends on the format, use Format::bytes_per_sample instead")]
 pub const I16_PER_SAMPLE: usize = 2;
 
 #[derive(Debug)]
 pub struct Device {
     dev: *mut ffi::bladerf,
+    /// Formats of the RX and TX sync interfaces, set by `sync_config`
+    rx_format: Option<Format>,
+    tx_format: Option<Format>,
 }
 
 impl Device {
@@ -70,7 +126,52 @@ impl Device {
         if dev.is_null() {
             return Err(Error::Invalid);
         }
-        Ok(Device { dev })
+        Ok(Device {
+            dev,
+            rx_format: None,
+            tx_format: None,
+        })
+    }
+
+    pub fn open_with_selector(selector: &DeviceSelector) -> Result<Self, Error> {
+        Device::open(&selector.to_string())
+    }
+
+    /// Open the device described by `info`, typically obtained from `Device::list`
+    pub fn open_with_info(info: &DeviceInfo) -> Result<Self, Error> {
+        let mut devinfo = ffi::bladerf_devinfo::try_from(info)?;
+        let mut dev = MaybeUninit::<*mut ffi::bladerf>::uninit();
+        let err = unsafe { ffi::bladerf_open_with_devinfo(dev.as_mut_ptr(), &mut devinfo) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        let dev = unsafe { dev.assume_init() };
+        if dev.is_null() {
+            return Err(Error::Invalid);
+        }
+        Ok(Device {
+            dev,
+            rx_format: None,
+            tx_format: None,
+        })
+    }
+
+    /// List the attached devices
+    pub fn list() -> Result<Vec<DeviceInfo>, Error> {
+        let mut devices: *mut ffi::bladerf_devinfo = std::ptr::null_mut();
+        let count = unsafe { ffi::bladerf_get_device_list(&mut devices) };
+        if count == ffi::BLADERF_ERR_NODEV {
+            return Ok(Vec::new());
+        } else if count < 0 {
+            return Err(Error::from(count));
+        }
+        let list = unsafe { DeviceList::from_raw(devices, count as usize) };
+        Ok(list
+            .as_slice()
+            .iter()
+            .copied()
+            .map(DeviceInfo::from)
+            .collect())
     }
 
     pub fn close(mut self) {
@@ -107,6 +208,137 @@ impl Device {
         slice.to_str().map_err(|_| Error::CString)
     }
 
+    pub fn firmware_version(&mut self) -> Result<Version, Error> {
+        let mut version = MaybeUninit::<ffi::bladerf_version>::uninit();
+        let err = unsafe { ffi::bladerf_fw_version(self.dev, version.as_mut_ptr()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        let version = unsafe { version.assume_init() };
+        Ok(Version::from_ffi(&version))
+    }
+
+    /// Fails if the FPGA is not loaded
+    pub fn fpga_version(&mut self) -> Result<Version, Error> {
+        let mut version = MaybeUninit::<ffi::bladerf_version>::uninit();
+        let err = unsafe { ffi::bladerf_fpga_version(self.dev, version.as_mut_ptr()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        let version = unsafe { version.assume_init() };
+        Ok(Version::from_ffi(&version))
+    }
+
+    /// Compare the firmware and FPGA versions against the versions expected
+    /// by the linked libbladeRF, an empty list means everything is up to date
+    ///
+    /// The firmware is still checked when no FPGA is loaded.
+    pub fn check_versions(&mut self) -> Result<Vec<VersionWarning>, Error> {
+        let firmware = self.firmware_version()?;
+        let fpga = if self.is_fpga_configured()? {
+            Some(self.fpga_version()?)
+        } else {
+            None
+        };
+        Ok(VersionWarning::check(lib_version(), firmware, fpga))
+    }
+
+    /// Returns true if the FPGA is loaded
+    pub fn is_fpga_configured(&mut self) -> Result<bool, Error> {
+        let ret = unsafe { ffi::bladerf_is_fpga_configured(self.dev) };
+        if ret < 0 {
+            return Err(Error::from(ret));
+        }
+        Ok(ret != 0)
+    }
+
+    pub fn fpga_size(&mut self) -> Result<FpgaSize, Error> {
+        let mut size = MaybeUninit::<ffi::bladerf_fpga_size>::uninit();
+        let err = unsafe { ffi::bladerf_get_fpga_size(self.dev, size.as_mut_ptr()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        let size = unsafe { size.assume_init() };
+        Ok(FpgaSize::from_ffi(size))
+    }
+
+    /// Size of a full bitstream for this board's FPGA, in bytes
+    pub fn fpga_bytes(&mut self) -> Result<usize, Error> {
+        let mut bytes = 0;
+        let err = unsafe { ffi::bladerf_get_fpga_bytes(self.dev, &mut bytes) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(bytes)
+    }
+
+    pub fn fpga_source(&mut self) -> Result<FpgaSource, Error> {
+        let mut source = MaybeUninit::<ffi::bladerf_fpga_source>::uninit();
+        let err = unsafe { ffi::bladerf_get_fpga_source(self.dev, source.as_mut_ptr()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        let source = unsafe { source.assume_init() };
+        Ok(FpgaSource::from_ffi(source))
+    }
+
+    /// Checks the size of an FPGA image file against the board's FPGA
+    fn check_fpga_image(&mut self, image: &Path) -> Result<CString, Error> {
+        let image_bytes = std::fs::metadata(image).map_err(|_| Error::Io)?.len();
+        check_fpga_image_size(image_bytes, self.fpga_bytes()?)?;
+        path_to_cstring(image)
+    }
+
+    /// Load an FPGA bitstream, the image is lost on power down
+    pub fn load_fpga<P: AsRef<Path>>(&mut self, image: P) -> Result<(), Error> {
+        let image = self.check_fpga_image(image.as_ref())?;
+        let err = unsafe { ffi::bladerf_load_fpga(self.dev, image.as_ptr()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+
+    /// Write an FPGA bitstream to flash, it is autoloaded on power up
+    pub fn flash_fpga<P: AsRef<Path>>(&mut self, image: P) -> Result<(), Error> {
+        let image = self.check_fpga_image(image.as_ref())?;
+        let err = unsafe { ffi::bladerf_flash_fpga(self.dev, image.as_ptr()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+
+    /// Erase the FPGA image stored in flash, disabling autoload
+    pub fn erase_stored_fpga(&mut self) -> Result<(), Error> {
+        let err = unsafe { ffi::bladerf_erase_stored_fpga(self.dev) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+
+    /// Write a firmware image to flash, it is used after the next power cycle
+    pub fn flash_firmware<P: AsRef<Path>>(&mut self, image: P) -> Result<(), Error> {
+        let image = path_to_cstring(image.as_ref())?;
+        let err = unsafe { ffi::bladerf_flash_firmware(self.dev, image.as_ptr()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+
+    /// Erase the firmware signature and jump to the FX3 bootloader
+    ///
+    /// The device is closed afterwards, it re-enumerates as a `Bootloader`.
+    pub fn jump_to_bootloader(self) -> Result<(), Error> {
+        let err = unsafe { ffi::bladerf_jump_to_bootloader(self.dev) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+
     pub fn set_sample_rate<T: Into<Sps>>(
         &mut self,
         ch: Channel,
@@ -129,6 +361,17 @@ impl Device {
         Ok(actual.sps())
     }
 
+    pub fn sample_rate(&mut self, ch: Channel) -> Result<SampleRate, Error> {
+        let mut rate = MaybeUninit::<ffi::bladerf_sample_rate>::uninit();
+        let err =
+            unsafe { ffi::bladerf_get_sample_rate(self.dev, ch.into_ffi(), rate.as_mut_ptr()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        let rate = unsafe { rate.assume_init() };
+        Ok(rate.sps())
+    }
+
     pub fn set_bandwidth<T: Into<Hertz>>(
         &mut self,
         ch: Channel,
@@ -151,6 +394,16 @@ impl Device {
         Ok(actual.hz())
     }
 
+    pub fn bandwidth(&mut self, ch: Channel) -> Result<Bandwidth, Error> {
+        let mut bw = MaybeUninit::<ffi::bladerf_bandwidth>::uninit();
+        let err = unsafe { ffi::bladerf_get_bandwidth(self.dev, ch.into_ffi(), bw.as_mut_ptr()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        let bw = unsafe { bw.assume_init() };
+        Ok(bw.hz())
+    }
+
     pub fn set_frequency<T: Into<Hertz>>(
         &mut self,
         ch: Channel,
@@ -164,6 +417,607 @@ impl Device {
         Ok(())
     }
 
+    pub fn frequency(&mut self, ch: Channel) -> Result<Frequency, Error> {
+        let mut freq = MaybeUninit::<ffi::bladerf_frequency>::uninit();
+        let err = unsafe { ffi::bladerf_get_frequency(self.dev, ch.into_ffi(), freq.as_mut_ptr()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        let freq = unsafe { freq.assume_init() };
+        Ok(freq.hz())
+    }
+
+    pub fn set_tuning_mode(&mut self, mode: TuningMode) -> Result<(), Error> {
+        let err = unsafe { ffi::bladerf_set_tuning_mode(self.dev, mode.into_ffi()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+
+    pub fn tuning_mode(&mut self) -> Result<TuningMode, Error> {
+        let mut mode = MaybeUninit::<ffi::bladerf_tuning_mode>::uninit();
+        let err = unsafe { ffi::bladerf_get_tuning_mode(self.dev, mode.as_mut_ptr()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        let mode = unsafe { mode.assume_init() };
+        TuningMode::from_ffi(mode)
+    }
+
+    /// Capture the quick tune parameters of the channel's current frequency
+    pub fn quick_tune(&mut self, ch: Channel) -> Result<QuickTune, Error> {
+        let mut qt = MaybeUninit::<ffi::bladerf_quick_tune>::zeroed();
+        let err = unsafe { ffi::bladerf_get_quick_tune(self.dev, ch.into_ffi(), qt.as_mut_ptr()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        let qt = unsafe { qt.assume_init() };
+        Ok(QuickTune::from_ffi(&qt))
+    }
+
+    /// Schedule a retune to `frequency` at `timestamp`, `Timestamp(0)` retunes immediately
+    ///
+    /// When `quick_tune` is provided, `frequency` is ignored by libbladeRF and
+    /// the cached tuning parameters are applied instead.
+    pub fn schedule_retune<T: Into<Hertz>>(
+        &mut self,
+        ch: Channel,
+        timestamp: Timestamp,
+        frequency: T,
+        quick_tune: Option<&QuickTune>,
+    ) -> Result<(), Error> {
+        let hertz: Hertz = frequency.into();
+        let mut qt = quick_tune.map(|qt| qt.into_ffi());
+        let qt_ptr = match qt.as_mut() {
+            Some(qt) => qt as *mut _,
+            None => std::ptr::null_mut(),
+        };
+        let err = unsafe {
+            ffi::bladerf_schedule_retune(self.dev, ch.into_ffi(), timestamp.0, hertz.0, qt_ptr)
+        };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+
+    pub fn cancel_scheduled_retunes(&mut self, ch: Channel) -> Result<(), Error> {
+        let err = unsafe { ffi::bladerf_cancel_scheduled_retunes(self.dev, ch.into_ffi()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+
+    pub fn set_gain<T: Into<Gain>>(&mut self, ch: Channel, gain: T) -> Result<(), Error> {
+        let gain: Gain = gain.into();
+        let err = unsafe { ffi::bladerf_set_gain(self.dev, ch.into_ffi(), gain.0) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+
+    pub fn gain(&mut self, ch: Channel) -> Result<Gain, Error> {
+        let mut gain = MaybeUninit::<ffi::bladerf_gain>::uninit();
+        let err = unsafe { ffi::bladerf_get_gain(self.dev, ch.into_ffi(), gain.as_mut_ptr()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        let gain = unsafe { gain.assume_init() };
+        Ok(Gain(gain))
+    }
+
+    pub fn set_gain_mode(&mut self, ch: Channel, mode: GainMode) -> Result<(), Error> {
+        let err = unsafe { ffi::bladerf_set_gain_mode(self.dev, ch.into_ffi(), mode.into_ffi()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+
+    pub fn gain_mode(&mut self, ch: Channel) -> Result<GainMode, Error> {
+        let mut mode = MaybeUninit::<ffi::bladerf_gain_mode>::uninit();
+        let err = unsafe { ffi::bladerf_get_gain_mode(self.dev, ch.into_ffi(), mode.as_mut_ptr()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        let mode = unsafe { mode.assume_init() };
+        Ok(GainMode::from_ffi(mode))
+    }
+
+    /// Gain modes supported by the channel
+    pub fn gain_modes(&mut self, ch: Channel) -> Result<Vec<GainMode>, Error> {
+        let mut modes: *const ffi::bladerf_gain_modes = std::ptr::null();
+        let count = unsafe { ffi::bladerf_get_gain_modes(self.dev, ch.into_ffi(), &mut modes) };
+        if count < 0 {
+            return Err(Error::from(count));
+        }
+        if count == 0 || modes.is_null() {
+            return Ok(Vec::new());
+        }
+        let modes = unsafe { std::slice::from_raw_parts(modes, count as usize) };
+        Ok(modes.iter().map(|m| GainMode::from_ffi(m.mode)).collect())
+    }
+
+    /// Names of the individual gain stages of the channel
+    pub fn gain_stages(&mut self, ch: Channel) -> Result<Vec<String>, Error> {
+        let count = unsafe {
+            ffi::bladerf_get_gain_stages(self.dev, ch.into_ffi(), std::ptr::null_mut(), 0)
+        };
+        if count < 0 {
+            return Err(Error::from(count));
+        }
+        let mut stages = vec![std::ptr::null(); count as usize];
+        let count = unsafe {
+            ffi::bladerf_get_gain_stages(self.dev, ch.into_ffi(), stages.as_mut_ptr(), stages.len())
+        };
+        if count < 0 {
+            return Err(Error::from(count));
+        }
+        stages
+            .iter()
+            .take(count as usize)
+            .map(|s| {
+                let slice = unsafe { CStr::from_ptr(*s) };
+                slice
+                    .to_str()
+                    .map(str::to_owned)
+                    .map_err(|_| Error::CString)
+            })
+            .collect()
+    }
+
+    pub fn set_gain_stage<T: Into<Gain>>(
+        &mut self,
+        ch: Channel,
+        stage: &str,
+        gain: T,
+    ) -> Result<(), Error> {
+        let gain: Gain = gain.into();
+        let stage_cstr = CString::new(stage).map_err(|_| Error::CString)?;
+        let err = unsafe {
+            ffi::bladerf_set_gain_stage(self.dev, ch.into_ffi(), stage_cstr.as_ptr(), gain.0)
+        };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+
+    pub fn gain_stage(&mut self, ch: Channel, stage: &str) -> Result<Gain, Error> {
+        let stage_cstr = CString::new(stage).map_err(|_| Error::CString)?;
+        let mut gain = MaybeUninit::<ffi::bladerf_gain>::uninit();
+        let err = unsafe {
+            ffi::bladerf_get_gain_stage(
+                self.dev,
+                ch.into_ffi(),
+                stage_cstr.as_ptr(),
+                gain.as_mut_ptr(),
+            )
+        };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        let gain = unsafe { gain.assume_init() };
+        Ok(Gain(gain))
+    }
+
+    pub fn gain_stage_range(&mut self, ch: Channel, stage: &str) -> Result<Range<Gain>, Error> {
+        let stage_cstr = CString::new(stage).map_err(|_| Error::CString)?;
+        let mut range: *const ffi::bladerf_range = std::ptr::null();
+        let err = unsafe {
+            ffi::bladerf_get_gain_stage_range(
+                self.dev,
+                ch.into_ffi(),
+                stage_cstr.as_ptr(),
+                &mut range,
+            )
+        };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        let range = unsafe { range.as_ref() }.ok_or(Error::Unexpected)?;
+        Ok(Range::from_ffi(range))
+    }
+
+    pub fn frequency_range(&mut self, ch: Channel) -> Result<Range<Frequency>, Error> {
+        let mut range: *const ffi::bladerf_range = std::ptr::null();
+        let err = unsafe { ffi::bladerf_get_frequency_range(self.dev, ch.into_ffi(), &mut range) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        let range = unsafe { range.as_ref() }.ok_or(Error::Unexpected)?;
+        Ok(Range::from_ffi(range))
+    }
+
+    pub fn sample_rate_range(&mut self, ch: Channel) -> Result<Range<SampleRate>, Error> {
+        let mut range: *const ffi::bladerf_range = std::ptr::null();
+        let err =
+            unsafe { ffi::bladerf_get_sample_rate_range(self.dev, ch.into_ffi(), &mut range) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        let range = unsafe { range.as_ref() }.ok_or(Error::Unexpected)?;
+        Ok(Range::from_ffi(range))
+    }
+
+    pub fn bandwidth_range(&mut self, ch: Channel) -> Result<Range<Bandwidth>, Error> {
+        let mut range: *const ffi::bladerf_range = std::ptr::null();
+        let err = unsafe { ffi::bladerf_get_bandwidth_range(self.dev, ch.into_ffi(), &mut range) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        let range = unsafe { range.as_ref() }.ok_or(Error::Unexpected)?;
+        Ok(Range::from_ffi(range))
+    }
+
+    /// Overall (system) gain range of the channel
+    pub fn gain_range(&mut self, ch: Channel) -> Result<Range<Gain>, Error> {
+        let mut range: *const ffi::bladerf_range = std::ptr::null();
+        let err = unsafe { ffi::bladerf_get_gain_range(self.dev, ch.into_ffi(), &mut range) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        let range = unsafe { range.as_ref() }.ok_or(Error::Unexpected)?;
+        Ok(Range::from_ffi(range))
+    }
+
+    /// Query all of the supported tuning ranges of the channel
+    pub fn capabilities(&mut self, ch: Channel) -> Result<Capabilities, Error> {
+        Ok(Capabilities {
+            frequency: self.frequency_range(ch)?,
+            sample_rate: self.sample_rate_range(ch)?,
+            bandwidth: self.bandwidth_range(ch)?,
+            gain: self.gain_range(ch)?,
+        })
+    }
+
+    /// Read the current device timestamp of the RX or TX sample counter
+    pub fn timestamp(&mut self, dir: Direction) -> Result<Timestamp, Error> {
+        let mut ts = MaybeUninit::<ffi::bladerf_timestamp>::uninit();
+        let err = unsafe { ffi::bladerf_get_timestamp(self.dev, dir.into_ffi(), ts.as_mut_ptr()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        let ts = unsafe { ts.assume_init() };
+        Ok(Timestamp(ts))
+    }
+
+    /// RF ports available on the channel
+    pub fn rf_ports(&mut self, ch: Channel) -> Result<Vec<RfPort>, Error> {
+        let count =
+            unsafe { ffi::bladerf_get_rf_ports(self.dev, ch.into_ffi(), std::ptr::null_mut(), 0) };
+        if count < 0 {
+            return Err(Error::from(count));
+        }
+        let mut ports = vec![std::ptr::null(); count as usize];
+        let count = unsafe {
+            ffi::bladerf_get_rf_ports(
+                self.dev,
+                ch.into_ffi(),
+                ports.as_mut_ptr(),
+                ports.len().try_into().map_err(|_| Error::Range)?,
+            )
+        };
+        if count < 0 {
+            return Err(Error::from(count));
+        }
+        ports
+            .iter()
+            .take(count as usize)
+            .map(|p| {
+                let slice = unsafe { CStr::from_ptr(*p) };
+                let name = slice.to_str().map_err(|_| Error::CString)?;
+                Ok(RfPort::from(name))
+            })
+            .collect()
+    }
+
+    pub fn rf_port(&mut self, ch: Channel) -> Result<RfPort, Error> {
+        let mut port: *const std::os::raw::c_char = std::ptr::null();
+        let err = unsafe { ffi::bladerf_get_rf_port(self.dev, ch.into_ffi(), &mut port) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        if port.is_null() {
+            return Err(Error::Unexpected);
+        }
+        let slice = unsafe { CStr::from_ptr(port) };
+        let name = slice.to_str().map_err(|_| Error::CString)?;
+        Ok(RfPort::from(name))
+    }
+
+    /// Select the RF port by name, accepts an `RfPort` or a `&str`
+    pub fn set_rf_port<P: AsRef<str>>(&mut self, ch: Channel, port: P) -> Result<(), Error> {
+        let port_cstr = CString::new(port.as_ref()).map_err(|_| Error::CString)?;
+        let err = unsafe { ffi::bladerf_set_rf_port(self.dev, ch.into_ffi(), port_cstr.as_ptr()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+
+    pub fn set_bias_tee(&mut self, ch: Channel, enable: bool) -> Result<(), Error> {
+        let err = unsafe { ffi::bladerf_set_bias_tee(self.dev, ch.into_ffi(), enable) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+
+    pub fn bias_tee(&mut self, ch: Channel) -> Result<bool, Error> {
+        let mut enabled = false;
+        let err = unsafe { ffi::bladerf_get_bias_tee(self.dev, ch.into_ffi(), &mut enabled) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(enabled)
+    }
+
+    /// Enable the bias tee of the channel until the returned guard is dropped
+    pub fn enable_bias_tee(&mut self, ch: Channel) -> Result<BiasTeeGuard<'_>, Error> {
+        self.set_bias_tee(ch, true)?;
+        Ok(BiasTeeGuard::new(self, ch))
+    }
+
+    pub fn set_loopback(&mut self, lb: Loopback) -> Result<(), Error> {
+        let err = unsafe { ffi::bladerf_set_loopback(self.dev, lb.into_ffi()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+
+    pub fn loopback(&mut self) -> Result<Loopback, Error> {
+        let mut lb = MaybeUninit::<ffi::bladerf_loopback>::uninit();
+        let err = unsafe { ffi::bladerf_get_loopback(self.dev, lb.as_mut_ptr()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        let lb = unsafe { lb.assume_init() };
+        Ok(Loopback::from_ffi(lb))
+    }
+
+    /// Loopback modes supported by the device
+    pub fn supported_loopbacks(&mut self) -> Result<Vec<Loopback>, Error> {
+        let mut modes: *const ffi::bladerf_loopback_modes = std::ptr::null();
+        let count = unsafe { ffi::bladerf_get_loopback_modes(self.dev, &mut modes) };
+        if count < 0 {
+            return Err(Error::from(count));
+        }
+        if count == 0 || modes.is_null() {
+            return Ok(Vec::new());
+        }
+        let modes = unsafe { std::slice::from_raw_parts(modes, count as usize) };
+        Ok(modes.iter().map(|m| Loopback::from_ffi(m.mode)).collect())
+    }
+
+    pub fn set_rx_mux(&mut self, mux: RxMux) -> Result<(), Error> {
+        let err = unsafe { ffi::bladerf_set_rx_mux(self.dev, mux.into_ffi()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+
+    pub fn rx_mux(&mut self) -> Result<RxMux, Error> {
+        let mut mux = MaybeUninit::<ffi::bladerf_rx_mux>::uninit();
+        let err = unsafe { ffi::bladerf_get_rx_mux(self.dev, mux.as_mut_ptr()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        let mux = unsafe { mux.assume_init() };
+        RxMux::from_ffi(mux)
+    }
+
+    /// Initialize a trigger for `ch` on the given signal line
+    ///
+    /// The returned trigger has its role disabled, see `Trigger::with_role`.
+    pub fn trigger_init(&mut self, ch: Channel, signal: TriggerSignal) -> Result<Trigger, Error> {
+        let mut trigger = MaybeUninit::<ffi::bladerf_trigger>::uninit();
+        let err = unsafe {
+            ffi::bladerf_trigger_init(
+                self.dev,
+                ch.into_ffi(),
+                signal.into_ffi(),
+                trigger.as_mut_ptr(),
+            )
+        };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        let trigger = unsafe { trigger.assume_init() };
+        Trigger::from_ffi(ch, &trigger)
+    }
+
+    /// Arm or disarm a trigger
+    ///
+    /// While armed, samples on the trigger's channel are held off until the
+    /// trigger fires.
+    pub fn trigger_arm(&mut self, trigger: &Trigger, arm: bool) -> Result<(), Error> {
+        let trigger = trigger.into_ffi();
+        let err = unsafe { ffi::bladerf_trigger_arm(self.dev, &trigger, arm, 0, 0) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+
+    /// Fire a trigger, only valid for the master
+    pub fn trigger_fire(&mut self, trigger: &Trigger) -> Result<(), Error> {
+        if trigger.role() != TriggerRole::Master {
+            return Err(Error::Invalid);
+        }
+        let trigger = trigger.into_ffi();
+        let err = unsafe { ffi::bladerf_trigger_fire(self.dev, &trigger) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+
+    pub fn trigger_state(&mut self, trigger: &Trigger) -> Result<TriggerState, Error> {
+        let trigger = trigger.into_ffi();
+        let mut state = TriggerState::default();
+        let err = unsafe {
+            ffi::bladerf_trigger_state(
+                self.dev,
+                &trigger,
+                &mut state.armed,
+                &mut state.fired,
+                &mut state.fire_requested,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(state)
+    }
+
+    pub fn set_clock_select(&mut self, sel: ClockSelect) -> Result<(), Error> {
+        let err = unsafe { ffi::bladerf_set_clock_select(self.dev, sel.into_ffi()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+
+    pub fn clock_select(&mut self) -> Result<ClockSelect, Error> {
+        let mut sel = MaybeUninit::<ffi::bladerf_clock_select>::uninit();
+        let err = unsafe { ffi::bladerf_get_clock_select(self.dev, sel.as_mut_ptr()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        let sel = unsafe { sel.assume_init() };
+        Ok(ClockSelect::from_ffi(sel))
+    }
+
+    pub fn set_clock_output(&mut self, enable: bool) -> Result<(), Error> {
+        let err = unsafe { ffi::bladerf_set_clock_output(self.dev, enable) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+
+    pub fn clock_output(&mut self) -> Result<bool, Error> {
+        let mut enabled = false;
+        let err = unsafe { ffi::bladerf_get_clock_output(self.dev, &mut enabled) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(enabled)
+    }
+
+    pub fn set_pll_enable(&mut self, enable: bool) -> Result<(), Error> {
+        let err = unsafe { ffi::bladerf_set_pll_enable(self.dev, enable) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+
+    pub fn pll_enabled(&mut self) -> Result<bool, Error> {
+        let mut enabled = false;
+        let err = unsafe { ffi::bladerf_get_pll_enable(self.dev, &mut enabled) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(enabled)
+    }
+
+    pub fn set_pll_refclk<T: Into<Hertz>>(&mut self, frequency: T) -> Result<(), Error> {
+        let hertz: Hertz = frequency.into();
+        let err = unsafe { ffi::bladerf_set_pll_refclk(self.dev, hertz.0) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+
+    pub fn pll_refclk(&mut self) -> Result<Frequency, Error> {
+        let mut freq = 0;
+        let err = unsafe { ffi::bladerf_get_pll_refclk(self.dev, &mut freq) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(Hertz(freq))
+    }
+
+    pub fn pll_refclk_range(&mut self) -> Result<Range<Frequency>, Error> {
+        let mut range: *const ffi::bladerf_range = std::ptr::null();
+        let err = unsafe { ffi::bladerf_get_pll_refclk_range(self.dev, &mut range) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        let range = unsafe { range.as_ref() }.ok_or(Error::Unexpected)?;
+        Ok(Range::from_ffi(range))
+    }
+
+    pub fn pll_locked(&mut self) -> Result<bool, Error> {
+        let mut locked = false;
+        let err = unsafe { ffi::bladerf_get_pll_lock_state(self.dev, &mut locked) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(locked)
+    }
+
+    /// Poll the PLL lock state until it locks, returns `Error::Timeout`
+    /// if it does not lock within `timeout`
+    pub fn wait_pll_lock(&mut self, timeout: Duration) -> Result<(), Error> {
+        let start = Instant::now();
+        while !self.pll_locked()? {
+            if start.elapsed() >= timeout {
+                return Err(Error::Timeout);
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        Ok(())
+    }
+
+    pub fn clock_config(&mut self) -> Result<ClockConfig, Error> {
+        let pll_refclk = if self.pll_enabled()? {
+            Some(self.pll_refclk()?)
+        } else {
+            None
+        };
+        Ok(ClockConfig {
+            select: self.clock_select()?,
+            output: self.clock_output()?,
+            pll_refclk,
+        })
+    }
+
+    /// Apply a reference clock configuration
+    ///
+    /// The PLL reference frequency is checked against `pll_refclk_range`
+    /// before anything is changed. Use `wait_pll_lock` afterwards to verify
+    /// lock before streaming.
+    pub fn set_clock_config(&mut self, config: &ClockConfig) -> Result<(), Error> {
+        match config.pll_refclk {
+            Some(refclk) => {
+                if !self.pll_refclk_range()?.contains(refclk) {
+                    return Err(Error::Range);
+                }
+                self.set_pll_refclk(refclk)?;
+                self.set_pll_enable(true)?;
+            }
+            None => self.set_pll_enable(false)?,
+        }
+        self.set_clock_select(config.select)?;
+        self.set_clock_output(config.output)
+    }
+
+    /// Access the VCTCXO trim DAC and tamer
+    pub fn vctcxo(&mut self) -> Vctcxo<'_> {
+        Vctcxo::new(self)
+    }
+
     pub fn sync_config(
         &mut self,
         layout: ChannelLayout,
@@ -173,7 +1027,7 @@ impl Device {
         num_transfers: usize,
         stream_timeout: MilliSeconds,
     ) -> Result<(), Error> {
-        if samples_per_buffer % SAMPLES_PER_BUFFER != 0 {
+        if samples_per_buffer % format.samples_per_buffer_multiple() != 0 {
             return Err(Error::SamplesPerBuffer);
         }
         let err = unsafe {
@@ -190,9 +1044,30 @@ impl Device {
         if err != 0 {
             return Err(Error::from(err));
         }
+        if layout.is_tx() {
+            self.tx_format = Some(format);
+        } else {
+            self.rx_format = Some(format);
+        }
         Ok(())
     }
 
+    fn check_sync_format<S: Sample>(
+        &self,
+        dir: Direction,
+        has_metadata: bool,
+    ) -> Result<(), Error> {
+        let format = match dir {
+            Direction::Rx => self.rx_format,
+            Direction::Tx => self.tx_format,
+        };
+        match format {
+            Some(format) if !S::is_compatible(format) => Err(Error::SampleFormat),
+            Some(format) if format.has_metadata() && !has_metadata => Err(Error::Invalid),
+            _ => Ok(()),
+        }
+    }
+
     pub fn enable_module(&mut self, ch: Channel, enable: bool) -> Result<(), Error> {
         let err = unsafe { ffi::bladerf_enable_module(self.dev, ch.into_ffi(), enable) };
         if err != 0 {
@@ -201,16 +1076,14 @@ impl Device {
         Ok(())
     }
 
-    pub fn sync_rx(
+    pub fn sync_rx<S: Sample>(
         &mut self,
-        samples: &mut [i16],
+        samples: &mut [S],
         metadata: Option<&mut Metadata>,
         timeout: MilliSeconds,
     ) -> Result<(), Error> {
-        if samples.len() % I16_PER_SAMPLE != 0 {
-            return Err(Error::SamplesLen);
-        }
-        let num_samples = samples.len() / 2;
+        self.check_sync_format::<S>(Direction::Rx, metadata.is_some())?;
+        let num_samples = samples.len();
         let md_ptr = if let Some(md_ref) = metadata {
             &mut md_ref.inner as *mut _
         } else {
@@ -230,6 +1103,65 @@ impl Device {
         }
         Ok(())
     }
+
+    /// Initialize an asynchronous stream, see `Stream`
+    ///
+    /// `num_transfers` must be less than `num_buffers` and the sample type
+    /// must match `format`.
+    pub fn init_stream<S, F>(
+        &mut self,
+        format: Format,
+        num_buffers: usize,
+        samples_per_buffer: usize,
+        num_transfers: usize,
+        callback: F,
+    ) -> Result<Stream<'_, S, F>, Error>
+    where
+        S: Sample,
+        F: FnMut(&mut [S]) -> StreamAction + Send,
+    {
+        Stream::new(
+            self,
+            format,
+            num_buffers,
+            samples_per_buffer,
+            num_transfers,
+            callback,
+        )
+    }
+
+    pub fn sync_tx<S: Sample>(
+        &mut self,
+        samples: &[S],
+        metadata: Option<&mut Metadata>,
+        timeout: MilliSeconds,
+    ) -> Result<(), Error> {
+        self.check_sync_format::<S>(Direction::Tx, metadata.is_some())?;
+        let num_samples = samples.len();
+        let md_ptr = if let Some(md_ref) = metadata {
+            &mut md_ref.inner as *mut _
+        } else {
+            std::ptr::null_mut()
+        };
+        let err = unsafe {
+            ffi::bladerf_sync_tx(
+                self.dev,
+                samples.as_ptr() as *const _,
+                num_samples.try_into().map_err(|_| Error::Range)?,
+                md_ptr,
+                timeout.0.try_into().map_err(|_| Error::Range)?,
+            )
+        };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+}
+
+fn path_to_cstring(path: &Path) -> Result<CString, Error> {
+    let path = path.to_str().ok_or(Error::CString)?;
+    CString::new(path).map_err(|_| Error::CString)
 }
 
 impl Drop for Device {
@@ -239,3 +1171,47 @@ impl Drop for Device {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unopened() -> Device {
+        Device {
+            dev: std::ptr::null_mut(),
+            rx_format: None,
+            tx_format: None,
+        }
+    }
+
+    #[test]
+    fn sync_format_per_direction() {
+        let mut dev = unopened();
+        dev.rx_format = Some(Format::Sc16Q11);
+        dev.tx_format = Some(Format::Sc8Q7Meta);
+        assert_eq!(
+            dev.check_sync_format::<Sc16Q11>(Direction::Rx, false),
+            Ok(())
+        );
+        assert_eq!(
+            dev.check_sync_format::<Sc8Q7>(Direction::Rx, false),
+            Err(Error::SampleFormat)
+        );
+        assert_eq!(dev.check_sync_format::<Sc8Q7>(Direction::Tx, true), Ok(()));
+        assert_eq!(
+            dev.check_sync_format::<Sc8Q7>(Direction::Tx, false),
+            Err(Error::Invalid)
+        );
+        assert_eq!(
+            dev.check_sync_format::<Sc16Q11>(Direction::Tx, true),
+            Err(Error::SampleFormat)
+        );
+
+        dev.rx_format = Some(Format::PacketMeta);
+        assert_eq!(dev.check_sync_format::<u32>(Direction::Rx, true), Ok(()));
+        assert_eq!(
+            dev.check_sync_format::<Sc16Q11>(Direction::Rx, true),
+            Err(Error::SampleFormat)
+        );
+    }
+}
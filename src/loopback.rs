@@ -0,0 +1,117 @@
+use crate::ffi;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum Loopback {
+    /// Loopback disabled
+    None,
+    /// Firmware loopback, TX samples are looped back to RX in the FX3
+    Firmware,
+    /// Baseband: TXLPF output to RXVGA2 input (bladeRF 1)
+    BbTxLpfRxVga2,
+    /// Baseband: TXVGA1 output to RXVGA2 input (bladeRF 1)
+    BbTxVga1RxVga2,
+    /// Baseband: TXLPF output to RXLPF input (bladeRF 1)
+    BbTxLpfRxLpf,
+    /// Baseband: TXVGA1 output to RXLPF input (bladeRF 1)
+    BbTxVga1RxLpf,
+    /// RF: TXMIX output to LNA1 (bladeRF 1)
+    RfLna1,
+    /// RF: TXMIX output to LNA2 (bladeRF 1)
+    RfLna2,
+    /// RF: TXMIX output to LNA3 (bladeRF 1)
+    RfLna3,
+    /// RFIC digital loopback (built-in self test, bladeRF 2)
+    RficBist,
+}
+
+impl Loopback {
+    pub(crate) fn into_ffi(self) -> ffi::bladerf_loopback {
+        use ffi::bladerf_loopback::*;
+        use Loopback::*;
+        match self {
+            None => BLADERF_LB_NONE,
+            Firmware => BLADERF_LB_FIRMWARE,
+            BbTxLpfRxVga2 => BLADERF_LB_BB_TXLPF_RXVGA2,
+            BbTxVga1RxVga2 => BLADERF_LB_BB_TXVGA1_RXVGA2,
+            BbTxLpfRxLpf => BLADERF_LB_BB_TXLPF_RXLPF,
+            BbTxVga1RxLpf => BLADERF_LB_BB_TXVGA1_RXLPF,
+            RfLna1 => BLADERF_LB_RF_LNA1,
+            RfLna2 => BLADERF_LB_RF_LNA2,
+            RfLna3 => BLADERF_LB_RF_LNA3,
+            RficBist => BLADERF_LB_RFIC_BIST,
+        }
+    }
+
+    pub(crate) fn from_ffi(lb: ffi::bladerf_loopback) -> Self {
+        use ffi::bladerf_loopback::*;
+        use Loopback::*;
+        match lb {
+            BLADERF_LB_NONE => None,
+            BLADERF_LB_FIRMWARE => Firmware,
+            BLADERF_LB_BB_TXLPF_RXVGA2 => BbTxLpfRxVga2,
+            BLADERF_LB_BB_TXVGA1_RXVGA2 => BbTxVga1RxVga2,
+            BLADERF_LB_BB_TXLPF_RXLPF => BbTxLpfRxLpf,
+            BLADERF_LB_BB_TXVGA1_RXLPF => BbTxVga1RxLpf,
+            BLADERF_LB_RF_LNA1 => RfLna1,
+            BLADERF_LB_RF_LNA2 => RfLna2,
+            BLADERF_LB_RF_LNA3 => RfLna3,
+            BLADERF_LB_RFIC_BIST => RficBist,
+        }
+    }
+}
+
+impl fmt::Display for Loopback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Loopback::*;
+        match self {
+            None => write!(f, "None"),
+            Firmware => write!(f, "Firmware"),
+            BbTxLpfRxVga2 => write!(f, "Baseband TXLPF -> RXVGA2"),
+            BbTxVga1RxVga2 => write!(f, "Baseband TXVGA1 -> RXVGA2"),
+            BbTxLpfRxLpf => write!(f, "Baseband TXLPF -> RXLPF"),
+            BbTxVga1RxLpf => write!(f, "Baseband TXVGA1 -> RXLPF"),
+            RfLna1 => write!(f, "RF LNA1"),
+            RfLna2 => write!(f, "RF LNA2"),
+            RfLna3 => write!(f, "RF LNA3"),
+            RficBist => write!(f, "RFIC BIST"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loopback_ffi() {
+        use ffi::bladerf_loopback::*;
+        use Loopback::*;
+        assert_eq!(None.into_ffi(), BLADERF_LB_NONE);
+        assert_eq!(Firmware.into_ffi(), BLADERF_LB_FIRMWARE);
+        assert_eq!(BbTxLpfRxVga2.into_ffi(), BLADERF_LB_BB_TXLPF_RXVGA2);
+        assert_eq!(BbTxVga1RxVga2.into_ffi(), BLADERF_LB_BB_TXVGA1_RXVGA2);
+        assert_eq!(BbTxLpfRxLpf.into_ffi(), BLADERF_LB_BB_TXLPF_RXLPF);
+        assert_eq!(BbTxVga1RxLpf.into_ffi(), BLADERF_LB_BB_TXVGA1_RXLPF);
+        assert_eq!(RfLna1.into_ffi(), BLADERF_LB_RF_LNA1);
+        assert_eq!(RfLna2.into_ffi(), BLADERF_LB_RF_LNA2);
+        assert_eq!(RfLna3.into_ffi(), BLADERF_LB_RF_LNA3);
+        assert_eq!(RficBist.into_ffi(), BLADERF_LB_RFIC_BIST);
+
+        for lb in [
+            None,
+            Firmware,
+            BbTxLpfRxVga2,
+            BbTxVga1RxVga2,
+            BbTxLpfRxLpf,
+            BbTxVga1RxLpf,
+            RfLna1,
+            RfLna2,
+            RfLna3,
+            RficBist,
+        ] {
+            assert_eq!(Loopback::from_ffi(lb.into_ffi()), lb);
+        }
+    }
+}
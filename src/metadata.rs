@@ -1,4 +1,6 @@
 use crate::ffi::bladerf_metadata;
+use crate::units::Timestamp;
+use crate::Error;
 use bitfield::bitfield;
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -89,9 +91,49 @@ impl Metadata {
     pub fn new_rx_now() -> Self {
         let mut flags = MetaFlags::default();
         flags.set_rx_now(true);
+        Metadata::with_flags(flags, Timestamp(0))
+    }
+
+    /// Receive samples starting at `timestamp`
+    pub fn new_rx_at(timestamp: Timestamp) -> Self {
+        Metadata::with_flags(MetaFlags::default(), timestamp)
+    }
+
+    /// First buffer of a TX burst, transmitted as soon as possible
+    pub fn new_tx_burst_start() -> Self {
+        let mut flags = MetaFlags::default();
+        flags.set_tx_burst_start(true);
+        flags.set_tx_now(true);
+        Metadata::with_flags(flags, Timestamp(0))
+    }
+
+    /// First buffer of a TX burst, scheduled for transmission at `timestamp`
+    pub fn new_tx_burst_start_at(timestamp: Timestamp) -> Self {
+        let mut flags = MetaFlags::default();
+        flags.set_tx_burst_start(true);
+        Metadata::with_flags(flags, timestamp)
+    }
+
+    /// Last buffer of a TX burst
+    pub fn new_tx_burst_end() -> Self {
+        let mut flags = MetaFlags::default();
+        flags.set_tx_burst_end(true);
+        Metadata::with_flags(flags, Timestamp(0))
+    }
+
+    /// A complete single-buffer TX burst, transmitted as soon as possible
+    pub fn new_tx_burst() -> Self {
+        let mut flags = MetaFlags::default();
+        flags.set_tx_burst_start(true);
+        flags.set_tx_burst_end(true);
+        flags.set_tx_now(true);
+        Metadata::with_flags(flags, Timestamp(0))
+    }
+
+    fn with_flags(flags: MetaFlags, timestamp: Timestamp) -> Self {
         Metadata {
             inner: bladerf_metadata {
-                timestamp: 0,
+                timestamp: timestamp.0,
                 flags: flags.0,
                 status: 0,
                 actual_count: 0,
@@ -108,8 +150,28 @@ impl Metadata {
         self.inner.reserved.iter_mut().for_each(|b| *b = 0);
     }
 
-    pub fn timestamp(&self) -> u64 {
-        self.inner.timestamp
+    pub fn timestamp(&self) -> Timestamp {
+        Timestamp(self.inner.timestamp)
+    }
+
+    pub fn set_timestamp(&mut self, timestamp: Timestamp) {
+        self.inner.timestamp = timestamp.0;
+    }
+
+    /// Receive at `timestamp` instead of immediately
+    pub fn schedule_rx(&mut self, timestamp: Timestamp) {
+        let mut flags = self.flags();
+        flags.set_rx_now(false);
+        self.set_flags(flags);
+        self.set_timestamp(timestamp);
+    }
+
+    /// Transmit at `timestamp` instead of immediately
+    pub fn schedule_tx(&mut self, timestamp: Timestamp) {
+        let mut flags = self.flags();
+        flags.set_tx_now(false);
+        self.set_flags(flags);
+        self.set_timestamp(timestamp);
     }
 
     pub fn flags(&self) -> MetaFlags {
@@ -121,12 +183,88 @@ impl Metadata {
     }
 
     pub fn status(&self) -> MetaStatus {
-        MetaStatus(self.inner.flags)
+        MetaStatus(self.inner.status)
     }
 
     pub fn actual_count(&self) -> u32 {
         self.inner.actual_count
     }
+
+    /// Summarize the status reported by the last `sync_rx`
+    ///
+    /// `requested` is the number of samples that was requested.
+    /// Only meaningful for RX metadata, the hardware underflow flag shares
+    /// its bit with `tx_burst_start`.
+    pub fn events(&self, requested: usize) -> Vec<MetadataEvent> {
+        let timestamp = self.timestamp();
+        let status = self.status();
+        let mut events = Vec::new();
+        if status.overrun() {
+            events.push(MetadataEvent::Overrun { timestamp });
+        }
+        if status.underrun() {
+            events.push(MetadataEvent::Underrun { timestamp });
+        }
+        if self.flags().rx_hw_underflow() {
+            events.push(MetadataEvent::HwUnderflow { timestamp });
+        }
+        let actual = self.actual_count() as usize;
+        if actual < requested {
+            events.push(MetadataEvent::ShortCount {
+                timestamp,
+                requested,
+                actual,
+            });
+        }
+        events
+    }
+
+    /// Returns the first event reported by the last `sync_rx` as an error
+    ///
+    /// `requested` is the number of samples that was requested.
+    pub fn check(&self, requested: usize) -> Result<(), Error> {
+        match self.events(requested).first() {
+            Some(event) => Err(Error::Metadata(*event)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Discontinuity reported through the metadata of a sync receive
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum MetadataEvent {
+    /// RX samples were dropped, the samples read start at `timestamp`
+    Overrun { timestamp: Timestamp },
+    /// TX samples were not provided in time
+    Underrun { timestamp: Timestamp },
+    /// The FPGA RX FIFO underflowed
+    HwUnderflow { timestamp: Timestamp },
+    /// Fewer samples than requested were read
+    ShortCount {
+        timestamp: Timestamp,
+        requested: usize,
+        actual: usize,
+    },
+}
+
+impl fmt::Display for MetadataEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use MetadataEvent::*;
+        match self {
+            Overrun { timestamp } => write!(f, "Overrun at t={}", timestamp),
+            Underrun { timestamp } => write!(f, "Underrun at t={}", timestamp),
+            HwUnderflow { timestamp } => write!(f, "Hardware underflow at t={}", timestamp),
+            ShortCount {
+                timestamp,
+                requested,
+                actual,
+            } => write!(
+                f,
+                "Short count at t={}, {} of {} samples",
+                timestamp, actual, requested
+            ),
+        }
+    }
 }
 
 impl From<bladerf_metadata> for Metadata {
@@ -190,5 +328,94 @@ mod tests {
         assert!(status.overrun());
         let status = MetaStatus(ffi::BLADERF_META_STATUS_UNDERRUN);
         assert!(status.underrun());
+
+        // Status is decoded from the status field, not the flags
+        let mut md = Metadata::new_rx_now();
+        assert!(!md.status().overrun());
+        md.inner.status = ffi::BLADERF_META_STATUS_OVERRUN;
+        assert!(md.status().overrun());
+        assert!(!md.status().underrun());
+    }
+
+    #[test]
+    fn metadata_events() {
+        let mut md = Metadata::new_rx_now();
+        md.inner.timestamp = 1000;
+        md.inner.actual_count = 1024;
+        assert!(md.events(1024).is_empty());
+        assert_eq!(md.check(1024), Ok(()));
+
+        md.inner.actual_count = 512;
+        md.inner.status = ffi::BLADERF_META_STATUS_OVERRUN;
+        let timestamp = Timestamp(1000);
+        assert_eq!(
+            md.events(1024),
+            vec![
+                MetadataEvent::Overrun { timestamp },
+                MetadataEvent::ShortCount {
+                    timestamp,
+                    requested: 1024,
+                    actual: 512
+                },
+            ]
+        );
+        assert_eq!(
+            md.check(1024),
+            Err(Error::Metadata(MetadataEvent::Overrun { timestamp }))
+        );
+
+        md.inner.status = 0;
+        md.inner.actual_count = 1024;
+        md.inner.flags = ffi::BLADERF_META_FLAG_RX_HW_UNDERFLOW;
+        assert_eq!(
+            md.check(1024),
+            Err(Error::Metadata(MetadataEvent::HwUnderflow { timestamp }))
+        );
+
+        md.inner.flags = 0;
+        md.inner.status = ffi::BLADERF_META_STATUS_UNDERRUN;
+        assert_eq!(md.events(0), vec![MetadataEvent::Underrun { timestamp }]);
+    }
+
+    #[test]
+    fn tx_burst_metadata() {
+        let md = Metadata::new_tx_burst_start();
+        assert_eq!(
+            md.flags().0,
+            ffi::BLADERF_META_FLAG_TX_BURST_START | ffi::BLADERF_META_FLAG_TX_NOW
+        );
+        assert_eq!(md.timestamp(), Timestamp(0));
+
+        let md = Metadata::new_tx_burst_start_at(Timestamp(1234));
+        assert_eq!(md.flags().0, ffi::BLADERF_META_FLAG_TX_BURST_START);
+        assert_eq!(md.timestamp(), Timestamp(1234));
+
+        let md = Metadata::new_tx_burst_end();
+        assert_eq!(md.flags().0, ffi::BLADERF_META_FLAG_TX_BURST_END);
+
+        let md = Metadata::new_tx_burst();
+        assert!(md.flags().tx_burst_start());
+        assert!(md.flags().tx_burst_end());
+        assert!(md.flags().tx_now());
+    }
+
+    #[test]
+    fn scheduled_metadata() {
+        let md = Metadata::new_rx_at(Timestamp(42));
+        assert!(!md.flags().rx_now());
+        assert_eq!(md.timestamp(), Timestamp(42));
+
+        let mut md = Metadata::new_rx_now();
+        md.schedule_rx(Timestamp(100));
+        assert_eq!(md.flags().0, 0);
+        assert_eq!(md.timestamp(), Timestamp(100));
+
+        let mut md = Metadata::new_tx_burst();
+        md.schedule_tx(Timestamp(200));
+        assert_eq!(
+            md.flags().0,
+            ffi::BLADERF_META_FLAG_TX_BURST_START | ffi::BLADERF_META_FLAG_TX_BURST_END
+        );
+        assert_eq!(md.timestamp(), Timestamp(200));
     }
 }
@@ -0,0 +1,56 @@
+use crate::ffi::bladerf_quick_tune;
+use serde::{Deserialize, Serialize};
+use std::mem::size_of;
+
+const QUICK_TUNE_SIZE: usize = size_of::<bladerf_quick_tune>();
+
+/// Opaque quick tune parameters for a frequency, obtained from `Device::quick_tune`
+///
+/// The contents are board specific and stored in native byte order, so a
+/// serialized value is only valid for the board family (and host) it came from.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct QuickTune {
+    raw: [u8; QUICK_TUNE_SIZE],
+}
+
+impl QuickTune {
+    pub(crate) fn into_ffi(self) -> bladerf_quick_tune {
+        unsafe { std::ptr::read_unaligned(self.raw.as_ptr() as *const bladerf_quick_tune) }
+    }
+
+    pub(crate) fn from_ffi(qt: &bladerf_quick_tune) -> Self {
+        let mut raw = [0; QUICK_TUNE_SIZE];
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                qt as *const bladerf_quick_tune as *const u8,
+                raw.as_mut_ptr(),
+                QUICK_TUNE_SIZE,
+            )
+        };
+        QuickTune { raw }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.raw
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quick_tune_round_trip() {
+        let mut raw = [0; QUICK_TUNE_SIZE];
+        raw.iter_mut()
+            .enumerate()
+            .for_each(|(i, b)| *b = i as u8 + 1);
+        let qt = QuickTune { raw };
+        let ffi_qt = qt.into_ffi();
+        assert_eq!(QuickTune::from_ffi(&ffi_qt), qt);
+
+        let json = serde_json::to_string(&qt).unwrap();
+        let de: QuickTune = serde_json::from_str(&json).unwrap();
+        assert_eq!(de, qt);
+    }
+}
@@ -0,0 +1,212 @@
+use crate::ffi;
+use crate::units::{Gain, Hertz, Sps};
+use serde::{Deserialize, Serialize};
+
+/// A unit that can be described by a libbladeRF `bladerf_range`
+pub trait RangeUnit: Copy + PartialOrd {
+    fn to_f64(self) -> f64;
+
+    fn from_f64(val: f64) -> Self;
+}
+
+impl RangeUnit for Hertz {
+    fn to_f64(self) -> f64 {
+        self.as_f64()
+    }
+
+    fn from_f64(val: f64) -> Self {
+        Hertz(val.round() as u64)
+    }
+}
+
+impl RangeUnit for Sps {
+    fn to_f64(self) -> f64 {
+        self.as_f64()
+    }
+
+    fn from_f64(val: f64) -> Self {
+        Sps(val.round() as u64)
+    }
+}
+
+impl RangeUnit for Gain {
+    fn to_f64(self) -> f64 {
+        self.as_f64()
+    }
+
+    fn from_f64(val: f64) -> Self {
+        Gain(val.round() as i32)
+    }
+}
+
+/// Values within this distance of an integer are treated as that integer,
+/// scale factors are `f32` and not exact
+fn snap(val: f64) -> Option<f64> {
+    let rounded = val.round();
+    let tolerance = 1e-6 * rounded.abs().max(1.0);
+    if (val - rounded).abs() <= tolerance {
+        Some(rounded)
+    } else {
+        None
+    }
+}
+
+fn ceil(val: f64) -> f64 {
+    snap(val).unwrap_or_else(|| val.ceil())
+}
+
+fn floor(val: f64) -> f64 {
+    snap(val).unwrap_or_else(|| val.floor())
+}
+
+/// A range of supported values, as reported by the device
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Range<T> {
+    pub min: T,
+    pub max: T,
+    pub step: T,
+    /// Scale factor that was applied to the raw libbladeRF values
+    pub scale: f32,
+}
+
+impl<T: RangeUnit> Range<T> {
+    /// Bounds are rounded inwards so every value in the range is supported,
+    /// a fractional step is rounded up so it never becomes 0
+    pub(crate) fn from_ffi(range: &ffi::bladerf_range) -> Self {
+        let scale = f64::from(range.scale);
+        Range {
+            min: T::from_f64(ceil(range.min as f64 * scale)),
+            max: T::from_f64(floor(range.max as f64 * scale)),
+            step: T::from_f64(ceil(range.step as f64 * scale)),
+            scale: range.scale,
+        }
+    }
+
+    /// Returns true if `val` lies within `[min, max]`
+    pub fn contains(&self, val: T) -> bool {
+        self.min <= val && val <= self.max
+    }
+
+    /// Limits `val` to `[min, max]`
+    pub fn clamp(&self, val: T) -> T {
+        if val < self.min {
+            self.min
+        } else if val > self.max {
+            self.max
+        } else {
+            val
+        }
+    }
+
+    /// Clamps `val` and rounds it to the nearest step above `min`
+    pub fn quantize(&self, val: T) -> T {
+        let val = self.clamp(val);
+        let step = self.step.to_f64();
+        if step <= 0.0 {
+            return val;
+        }
+        let min = self.min.to_f64();
+        let steps = ((val.to_f64() - min) / step).round();
+        let mut q = min + steps * step;
+        if q > self.max.to_f64() {
+            q -= step;
+        }
+        T::from_f64(q)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_from_ffi() {
+        // bladeRF2 TX dsa stage, -89.75 dB to 0 dB in 0.25 dB steps
+        let raw = ffi::bladerf_range {
+            min: -89750,
+            max: 0,
+            step: 250,
+            scale: 0.001,
+        };
+        let r = Range::<Gain>::from_ffi(&raw);
+        assert_eq!(r.min, Gain(-89));
+        assert_eq!(r.max, Gain(0));
+        assert_eq!(r.step, Gain(1));
+        assert!(!r.contains(Gain(-90)));
+        assert_eq!(r.quantize(Gain(-95)), Gain(-89));
+
+        let raw = ffi::bladerf_range {
+            min: -1250,
+            max: 60750,
+            step: 1000,
+            scale: 0.001,
+        };
+        let r = Range::<Gain>::from_ffi(&raw);
+        assert_eq!(r.min, Gain(-1));
+        assert_eq!(r.max, Gain(60));
+        assert_eq!(r.step, Gain(1));
+
+        // f32 scale factors are not exact, integral values stay put
+        let raw = ffi::bladerf_range {
+            min: 1000,
+            max: 6_000_000,
+            step: 1000,
+            scale: 0.001,
+        };
+        let r = Range::<Gain>::from_ffi(&raw);
+        assert_eq!(r.min, Gain(1));
+        assert_eq!(r.max, Gain(6000));
+        assert_eq!(r.step, Gain(1));
+
+        let raw = ffi::bladerf_range {
+            min: 70_000_000,
+            max: 6_000_000_000,
+            step: 2,
+            scale: 1.0,
+        };
+        let r = Range::<Hertz>::from_ffi(&raw);
+        assert_eq!(r.min, Hertz(70_000_000));
+        assert_eq!(r.max, Hertz(6_000_000_000));
+        assert_eq!(r.step, Hertz(2));
+    }
+
+    #[test]
+    fn range_contains_clamp() {
+        let r = Range {
+            min: Hertz(100),
+            max: Hertz(200),
+            step: Hertz(10),
+            scale: 1.0,
+        };
+        assert!(r.contains(Hertz(100)));
+        assert!(r.contains(Hertz(200)));
+        assert!(!r.contains(Hertz(99)));
+        assert!(!r.contains(Hertz(201)));
+        assert_eq!(r.clamp(Hertz(50)), Hertz(100));
+        assert_eq!(r.clamp(Hertz(250)), Hertz(200));
+        assert_eq!(r.clamp(Hertz(150)), Hertz(150));
+    }
+
+    #[test]
+    fn range_quantize() {
+        let r = Range {
+            min: Gain(-10),
+            max: Gain(27),
+            step: Gain(3),
+            scale: 1.0,
+        };
+        assert_eq!(r.quantize(Gain(-20)), Gain(-10));
+        assert_eq!(r.quantize(Gain(0)), Gain(-1));
+        assert_eq!(r.quantize(Gain(1)), Gain(2));
+        assert_eq!(r.quantize(Gain(27)), Gain(26));
+        assert_eq!(r.quantize(Gain(100)), Gain(26));
+
+        let r = Range {
+            min: Sps(1),
+            max: Sps(1000),
+            step: Sps(0),
+            scale: 1.0,
+        };
+        assert_eq!(r.quantize(Sps(512)), Sps(512));
+    }
+}
@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// RF port of a channel
+///
+/// Known names are those of the bladeRF 2.0 (AD9361) ports, any other
+/// name reported by libbladeRF is kept as `Other`.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum RfPort {
+    /// RX A balanced
+    ABalanced,
+    /// RX B balanced
+    BBalanced,
+    /// RX C balanced
+    CBalanced,
+    AN,
+    AP,
+    BN,
+    BP,
+    CN,
+    CP,
+    TxMon1,
+    TxMon2,
+    TxMon12,
+    /// TX A
+    TxA,
+    /// TX B
+    TxB,
+    Other(String),
+}
+
+impl RfPort {
+    /// Name used by libbladeRF
+    pub fn as_str(&self) -> &str {
+        use RfPort::*;
+        match self {
+            ABalanced => "A_BALANCED",
+            BBalanced => "B_BALANCED",
+            CBalanced => "C_BALANCED",
+            AN => "A_N",
+            AP => "A_P",
+            BN => "B_N",
+            BP => "B_P",
+            CN => "C_N",
+            CP => "C_P",
+            TxMon1 => "TX_MON1",
+            TxMon2 => "TX_MON2",
+            TxMon12 => "TX_MON1_2",
+            TxA => "TXA",
+            TxB => "TXB",
+            Other(name) => name,
+        }
+    }
+}
+
+impl From<&str> for RfPort {
+    fn from(s: &str) -> Self {
+        use RfPort::*;
+        match s {
+            "A_BALANCED" => ABalanced,
+            "B_BALANCED" => BBalanced,
+            "C_BALANCED" => CBalanced,
+            "A_N" => AN,
+            "A_P" => AP,
+            "B_N" => BN,
+            "B_P" => BP,
+            "C_N" => CN,
+            "C_P" => CP,
+            "TX_MON1" => TxMon1,
+            "TX_MON2" => TxMon2,
+            "TX_MON1_2" => TxMon12,
+            "TXA" => TxA,
+            "TXB" => TxB,
+            _ => Other(s.to_owned()),
+        }
+    }
+}
+
+impl FromStr for RfPort {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(RfPort::from(s))
+    }
+}
+
+impl AsRef<str> for RfPort {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for RfPort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rf_port_names() {
+        use RfPort::*;
+        let known = [
+            ABalanced, BBalanced, CBalanced, AN, AP, BN, BP, CN, CP, TxMon1, TxMon2, TxMon12, TxA,
+            TxB,
+        ];
+        for port in known {
+            assert_eq!(RfPort::from_str(port.as_str()), Ok(port));
+        }
+        assert_eq!(RfPort::from_str("rx"), Ok(RfPort::Other("rx".to_owned())));
+        assert_eq!(RfPort::Other("rx".to_owned()).to_string(), "rx");
+    }
+}
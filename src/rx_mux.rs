@@ -0,0 +1,173 @@
+use crate::{ffi, Error, Sc16Q11};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum RxMux {
+    /// Baseband samples from the RFIC
+    Baseband,
+    /// 12-bit counter, generated by the FPGA
+    Counter12Bit,
+    /// 32-bit counter, generated by the FPGA, I holds the lower 16 bits
+    /// and Q the upper 16 bits
+    Counter32Bit,
+    /// TX samples looped back to RX in the FPGA
+    DigitalLoopback,
+}
+
+impl RxMux {
+    pub(crate) fn into_ffi(self) -> ffi::bladerf_rx_mux {
+        use ffi::bladerf_rx_mux::*;
+        use RxMux::*;
+        match self {
+            Baseband => BLADERF_RX_MUX_BASEBAND,
+            Counter12Bit => BLADERF_RX_MUX_12BIT_COUNTER,
+            Counter32Bit => BLADERF_RX_MUX_32BIT_COUNTER,
+            DigitalLoopback => BLADERF_RX_MUX_DIGITAL_LOOPBACK,
+        }
+    }
+
+    pub(crate) fn from_ffi(mux: ffi::bladerf_rx_mux) -> Result<Self, Error> {
+        use ffi::bladerf_rx_mux::*;
+        use RxMux::*;
+        match mux {
+            BLADERF_RX_MUX_BASEBAND => Ok(Baseband),
+            BLADERF_RX_MUX_12BIT_COUNTER => Ok(Counter12Bit),
+            BLADERF_RX_MUX_32BIT_COUNTER => Ok(Counter32Bit),
+            BLADERF_RX_MUX_DIGITAL_LOOPBACK => Ok(DigitalLoopback),
+            BLADERF_RX_MUX_INVALID => Err(Error::Invalid),
+        }
+    }
+}
+
+impl fmt::Display for RxMux {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use RxMux::*;
+        match self {
+            Baseband => write!(f, "Baseband"),
+            Counter12Bit => write!(f, "12-bit counter"),
+            Counter32Bit => write!(f, "32-bit counter"),
+            DigitalLoopback => write!(f, "Digital loopback"),
+        }
+    }
+}
+
+/// A gap found by `check_counter32`
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, thiserror::Error)]
+#[error("Counter discontinuity at sample {index}, expected {expected}, got {actual}")]
+pub struct CounterGap {
+    /// Index of the first sample that does not continue the count
+    pub index: usize,
+    pub expected: u32,
+    pub actual: u32,
+}
+
+impl CounterGap {
+    /// Number of samples missing, assuming the counter did not wrap more than once
+    pub fn dropped(&self) -> u32 {
+        self.actual.wrapping_sub(self.expected)
+    }
+}
+
+fn counter32(s: &Sc16Q11) -> u32 {
+    (u32::from(s.q as u16) << 16) | u32::from(s.i as u16)
+}
+
+/// Verify that a buffer received with `RxMux::Counter32Bit` is contiguous
+///
+/// `expected` is the counter value of the first sample, typically the value
+/// returned for the previous buffer; `None` accepts any starting value.
+/// Returns the counter value expected for the first sample of the next buffer,
+/// which stays `None` for an empty buffer without an expected value.
+pub fn check_counter32(
+    samples: &[Sc16Q11],
+    expected: Option<u32>,
+) -> Result<Option<u32>, CounterGap> {
+    let mut expected = match (expected, samples.first()) {
+        (Some(e), _) => e,
+        (None, Some(s)) => counter32(s),
+        (None, None) => return Ok(None),
+    };
+    for (index, s) in samples.iter().enumerate() {
+        let actual = counter32(s);
+        if actual != expected {
+            return Err(CounterGap {
+                index,
+                expected,
+                actual,
+            });
+        }
+        expected = expected.wrapping_add(1);
+    }
+    Ok(Some(expected))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counter_samples(start: u32, len: usize) -> Vec<Sc16Q11> {
+        (0..len as u32)
+            .map(|n| {
+                let c = start.wrapping_add(n);
+                Sc16Q11::new(c as u16 as i16, (c >> 16) as u16 as i16)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rx_mux_ffi() {
+        use ffi::bladerf_rx_mux::*;
+        use RxMux::*;
+        assert_eq!(Baseband.into_ffi(), BLADERF_RX_MUX_BASEBAND);
+        assert_eq!(Counter12Bit.into_ffi(), BLADERF_RX_MUX_12BIT_COUNTER);
+        assert_eq!(Counter32Bit.into_ffi(), BLADERF_RX_MUX_32BIT_COUNTER);
+        assert_eq!(DigitalLoopback.into_ffi(), BLADERF_RX_MUX_DIGITAL_LOOPBACK);
+        for mux in [Baseband, Counter12Bit, Counter32Bit, DigitalLoopback] {
+            assert_eq!(RxMux::from_ffi(mux.into_ffi()), Ok(mux));
+        }
+        assert_eq!(RxMux::from_ffi(BLADERF_RX_MUX_INVALID), Err(Error::Invalid));
+    }
+
+    #[test]
+    fn counter32_contiguous() {
+        let buf = counter_samples(0xFFF0, 64);
+        assert_eq!(check_counter32(&buf, None), Ok(Some(0xFFF0 + 64)));
+        assert_eq!(check_counter32(&buf, Some(0xFFF0)), Ok(Some(0xFFF0 + 64)));
+        let next = counter_samples(0xFFF0 + 64, 16);
+        assert_eq!(
+            check_counter32(&next, Some(0xFFF0 + 64)),
+            Ok(Some(0xFFF0 + 80))
+        );
+
+        // Wraps around
+        let buf = counter_samples(u32::MAX - 1, 4);
+        assert_eq!(check_counter32(&buf, None), Ok(Some(2)));
+
+        // Empty buffers pass the expected value through
+        assert_eq!(check_counter32(&[], Some(5)), Ok(Some(5)));
+        assert_eq!(check_counter32(&[], None), Ok(None));
+        let next = check_counter32(&[], None).unwrap();
+        let buf = counter_samples(1000, 8);
+        assert_eq!(check_counter32(&buf, next), Ok(Some(1008)));
+    }
+
+    #[test]
+    fn counter32_gap() {
+        let mut buf = counter_samples(100, 8);
+        buf.drain(4..6);
+        let gap = check_counter32(&buf, None).unwrap_err();
+        assert_eq!(
+            gap,
+            CounterGap {
+                index: 4,
+                expected: 104,
+                actual: 106
+            }
+        );
+        assert_eq!(gap.dropped(), 2);
+
+        let buf = counter_samples(10, 4);
+        assert_eq!(check_counter32(&buf, Some(8)).unwrap_err().index, 0);
+    }
+}
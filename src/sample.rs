@@ -0,0 +1,185 @@
+use crate::Format;
+use bytemuck::{Pod, Zeroable};
+use num_complex::Complex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Sample type usable with the sync and stream interfaces
+///
+/// This trait is sealed, the sample layout must match what libbladeRF
+/// expects for the configured `Format`. `u32` is the raw packet word used
+/// with `Format::PacketMeta`.
+pub trait Sample: private::Sealed + Pod + Default {
+    /// Returns true if buffers of this sample type can be used with `format`
+    fn is_compatible(format: Format) -> bool;
+}
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for super::Sc16Q11 {}
+    impl Sealed for super::Sc8Q7 {}
+    impl Sealed for u32 {}
+}
+
+/// Signed, complex 16-bit Q11 sample, full scale is [-2048, 2047]
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Sc16Q11 {
+    pub i: i16,
+    pub q: i16,
+}
+
+/// Signed, complex 8-bit Q7 sample, full scale is [-128, 127]
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Sc8Q7 {
+    pub i: i8,
+    pub q: i8,
+}
+
+// Both types are repr(C) with no padding, and any bit pattern is valid
+unsafe impl Zeroable for Sc16Q11 {}
+unsafe impl Pod for Sc16Q11 {}
+unsafe impl Zeroable for Sc8Q7 {}
+unsafe impl Pod for Sc8Q7 {}
+
+const Q11_SCALE: f32 = 2048.0;
+const Q7_SCALE: f32 = 128.0;
+
+fn saturate(val: f32, scale: f32) -> f32 {
+    // NaN saturates to 0 via the `as` cast at the call site
+    (val * scale).round().clamp(-scale, scale - 1.0)
+}
+
+impl Sc16Q11 {
+    pub const fn new(i: i16, q: i16) -> Self {
+        Sc16Q11 { i, q }
+    }
+}
+
+impl Sc8Q7 {
+    pub const fn new(i: i8, q: i8) -> Self {
+        Sc8Q7 { i, q }
+    }
+}
+
+impl Sample for Sc16Q11 {
+    fn is_compatible(format: Format) -> bool {
+        matches!(format, Format::Sc16Q11 | Format::Sc16Q11Meta)
+    }
+}
+
+impl Sample for Sc8Q7 {
+    fn is_compatible(format: Format) -> bool {
+        matches!(format, Format::Sc8Q7 | Format::Sc8Q7Meta)
+    }
+}
+
+impl Sample for u32 {
+    fn is_compatible(format: Format) -> bool {
+        format == Format::PacketMeta
+    }
+}
+
+impl From<Sc16Q11> for Complex<f32> {
+    fn from(s: Sc16Q11) -> Self {
+        Complex::new(f32::from(s.i) / Q11_SCALE, f32::from(s.q) / Q11_SCALE)
+    }
+}
+
+impl From<Complex<f32>> for Sc16Q11 {
+    fn from(c: Complex<f32>) -> Self {
+        Sc16Q11 {
+            i: saturate(c.re, Q11_SCALE) as i16,
+            q: saturate(c.im, Q11_SCALE) as i16,
+        }
+    }
+}
+
+impl From<Sc8Q7> for Complex<f32> {
+    fn from(s: Sc8Q7) -> Self {
+        Complex::new(f32::from(s.i) / Q7_SCALE, f32::from(s.q) / Q7_SCALE)
+    }
+}
+
+impl From<Complex<f32>> for Sc8Q7 {
+    fn from(c: Complex<f32>) -> Self {
+        Sc8Q7 {
+            i: saturate(c.re, Q7_SCALE) as i8,
+            q: saturate(c.im, Q7_SCALE) as i8,
+        }
+    }
+}
+
+impl fmt::Display for Sc16Q11 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.i, self.q)
+    }
+}
+
+impl fmt::Display for Sc8Q7 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.i, self.q)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_layout() {
+        assert_eq!(std::mem::size_of::<Sc16Q11>(), 4);
+        assert_eq!(std::mem::size_of::<Sc8Q7>(), 2);
+
+        let raw: [i16; 4] = [1, -2, 3, -4];
+        let samples: &[Sc16Q11] = bytemuck::cast_slice(&raw);
+        assert_eq!(samples, &[Sc16Q11::new(1, -2), Sc16Q11::new(3, -4)]);
+    }
+
+    #[test]
+    fn sc16q11_complex() {
+        let c: Complex<f32> = Sc16Q11::new(2048, -1024).into();
+        assert_eq!(c, Complex::new(1.0, -0.5));
+        assert_eq!(
+            Sc16Q11::from(Complex::new(0.5, -0.25)),
+            Sc16Q11::new(1024, -512)
+        );
+        // Saturation
+        assert_eq!(
+            Sc16Q11::from(Complex::new(1.0, -1.0)),
+            Sc16Q11::new(2047, -2048)
+        );
+        assert_eq!(
+            Sc16Q11::from(Complex::new(100.0, f32::NEG_INFINITY)),
+            Sc16Q11::new(2047, -2048)
+        );
+        assert_eq!(
+            Sc16Q11::from(Complex::new(f32::NAN, 0.0)),
+            Sc16Q11::new(0, 0)
+        );
+    }
+
+    #[test]
+    fn sc8q7_complex() {
+        let c: Complex<f32> = Sc8Q7::new(-128, 64).into();
+        assert_eq!(c, Complex::new(-1.0, 0.5));
+        assert_eq!(Sc8Q7::from(Complex::new(0.25, -0.5)), Sc8Q7::new(32, -64));
+        assert_eq!(Sc8Q7::from(Complex::new(2.0, -2.0)), Sc8Q7::new(127, -128));
+    }
+
+    #[test]
+    fn sample_format() {
+        assert!(Sc16Q11::is_compatible(Format::Sc16Q11));
+        assert!(Sc16Q11::is_compatible(Format::Sc16Q11Meta));
+        assert!(!Sc16Q11::is_compatible(Format::Sc8Q7));
+        assert!(Sc8Q7::is_compatible(Format::Sc8Q7));
+        assert!(Sc8Q7::is_compatible(Format::Sc8Q7Meta));
+        assert!(!Sc8Q7::is_compatible(Format::Sc16Q11));
+        assert!(!Sc8Q7::is_compatible(Format::PacketMeta));
+        assert!(u32::is_compatible(Format::PacketMeta));
+        assert!(!u32::is_compatible(Format::Sc16Q11Meta));
+        assert!(!Sc16Q11::is_compatible(Format::PacketMeta));
+    }
+}
@@ -0,0 +1,594 @@
+use crate::{ffi, ChannelLayout, Device, Error, MilliSeconds, Sample};
+use std::any::Any;
+use std::cell::UnsafeCell;
+use std::collections::VecDeque;
+use std::convert::TryInto;
+use std::marker::PhantomData;
+use std::os::raw::c_void;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+/// `BLADERF_STREAM_NO_DATA`, bindgen does not generate pointer constants
+const STREAM_NO_DATA: *mut c_void = usize::MAX as *mut c_void;
+
+/// Returned by the stream callback to control the stream
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum StreamAction {
+    /// Keep streaming, the buffer is handed back to libbladeRF
+    Continue,
+    /// No data is available yet, the transfer slot is left idle until
+    /// a buffer is provided with `Stream::submit`
+    NoData,
+    /// Stop streaming, `Stream::start` returns once outstanding
+    /// transfers have completed
+    Shutdown,
+}
+
+/// Buffer ownership, buffers in flight are in neither field
+struct Slots {
+    /// Indices of the buffers not owned by libbladeRF, oldest first
+    free: VecDeque<usize>,
+    /// Transfer slots left idle by a callback returning `NoData`
+    idle: usize,
+}
+
+struct StreamState<S, F> {
+    /// Only accessed from the thread running `Stream::start`
+    callback: UnsafeCell<F>,
+    panic: UnsafeCell<Option<Box<dyn Any + Send + 'static>>>,
+    running: AtomicBool,
+    tx: AtomicBool,
+    buffers: *mut *mut c_void,
+    num_buffers: usize,
+    num_transfers: usize,
+    samples_per_buffer: usize,
+    slots: Mutex<Slots>,
+    _sample: PhantomData<S>,
+}
+
+impl<S: Sample, F> StreamState<S, F> {
+    fn lock_slots(&self) -> MutexGuard<'_, Slots> {
+        self.slots.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn buffer(&self, index: usize) -> *mut c_void {
+        unsafe { *self.buffers.add(index) }
+    }
+
+    fn index_of(&self, buffer: *mut c_void) -> Option<usize> {
+        if buffer.is_null() {
+            return None;
+        }
+        (0..self.num_buffers).find(|i| self.buffer(*i) == buffer)
+    }
+
+    /// Ownership when the stream starts
+    fn reset(&self, tx: bool) {
+        // The first num_transfers RX buffers are submitted by libbladeRF
+        // itself, TX buffers are all requested through the callback
+        let first_free = if tx { 0 } else { self.num_transfers };
+        let mut slots = self.lock_slots();
+        slots.free = (first_free..self.num_buffers).collect();
+        slots.idle = 0;
+    }
+
+    /// Claim an idle transfer slot and a free buffer for `Stream::submit`
+    fn claim(&self) -> Result<usize, Error> {
+        let mut slots = self.lock_slots();
+        if slots.idle == 0 {
+            return Err(Error::WouldBlock);
+        }
+        let index = slots.free.pop_front().ok_or(Error::WouldBlock)?;
+        slots.idle -= 1;
+        Ok(index)
+    }
+
+    /// Give back a claim that was not submitted
+    fn release(&self, index: usize) {
+        let mut slots = self.lock_slots();
+        slots.free.push_front(index);
+        slots.idle += 1;
+    }
+
+    /// # Safety
+    /// `buffer` must be one of the stream's buffers, not owned by libbladeRF
+    unsafe fn buffer_mut<'b>(&self, buffer: *mut c_void, num_samples: usize) -> &'b mut [S] {
+        let num_samples = num_samples.min(self.samples_per_buffer);
+        std::slice::from_raw_parts_mut(buffer as *mut S, num_samples)
+    }
+}
+
+/// Returns a claimed buffer if `Stream::submit` does not complete
+struct Claim<'s, S: Sample, F> {
+    state: &'s StreamState<S, F>,
+    index: Option<usize>,
+}
+
+impl<S: Sample, F> Drop for Claim<'_, S, F> {
+    fn drop(&mut self) {
+        if let Some(index) = self.index {
+            self.state.release(index);
+        }
+    }
+}
+
+/// Asynchronous stream
+///
+/// The stream allocates `num_buffers` sample buffers. For RX streams the
+/// callback receives each buffer after it has been filled. For TX streams
+/// the callback fills each buffer before it is transmitted. A buffer is
+/// only ever handed out while libbladeRF does not own it.
+///
+/// The sample type `S` must be compatible with the stream's `Format`.
+///
+/// The channel(s) must be enabled with `Device::enable_module` before
+/// calling `Stream::start`. The device stays reachable through
+/// `Stream::with_device`, e.g. to retune while streaming.
+pub struct Stream<'a, S, F> {
+    stream: *mut ffi::bladerf_stream,
+    state: Box<StreamState<S, F>>,
+    dev: Mutex<&'a mut Device>,
+}
+
+// The callback is only ever run by the single thread inside `start`,
+// all other shared state is synchronized. libbladeRF serializes access to
+// the device handle internally, so it may be used from any thread.
+unsafe impl<S: Sample, F: Send> Send for Stream<'_, S, F> {}
+unsafe impl<S: Sample, F: Send> Sync for Stream<'_, S, F> {}
+
+impl<'a, S, F> Stream<'a, S, F>
+where
+    S: Sample,
+    F: FnMut(&mut [S]) -> StreamAction + Send,
+{
+    pub(crate) fn new(
+        dev: &'a mut Device,
+        format: crate::Format,
+        num_buffers: usize,
+        samples_per_buffer: usize,
+        num_transfers: usize,
+        callback: F,
+    ) -> Result<Self, Error> {
+        if !S::is_compatible(format) {
+            return Err(Error::SampleFormat);
+        }
+        if samples_per_buffer % format.samples_per_buffer_multiple() != 0 {
+            return Err(Error::SamplesPerBuffer);
+        }
+        // One buffer must always be free to hand to the callback
+        if num_transfers == 0 || num_transfers >= num_buffers {
+            return Err(Error::Invalid);
+        }
+        let mut state = Box::new(StreamState {
+            callback: UnsafeCell::new(callback),
+            panic: UnsafeCell::new(None),
+            running: AtomicBool::new(false),
+            tx: AtomicBool::new(false),
+            buffers: std::ptr::null_mut(),
+            num_buffers,
+            num_transfers,
+            samples_per_buffer,
+            slots: Mutex::new(Slots {
+                free: VecDeque::with_capacity(num_buffers),
+                idle: 0,
+            }),
+            _sample: PhantomData,
+        });
+        let mut stream: *mut ffi::bladerf_stream = std::ptr::null_mut();
+        let err = unsafe {
+            ffi::bladerf_init_stream(
+                &mut stream,
+                dev.dev,
+                Some(stream_callback::<S, F>),
+                &mut state.buffers,
+                num_buffers,
+                format.into_ffi(),
+                samples_per_buffer,
+                num_transfers,
+                &*state as *const StreamState<S, F> as *mut c_void,
+            )
+        };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        if stream.is_null() || state.buffers.is_null() {
+            return Err(Error::Unexpected);
+        }
+        Ok(Stream {
+            stream,
+            state,
+            dev: Mutex::new(dev),
+        })
+    }
+
+    /// Run the stream, blocking until the callback returns `StreamAction::Shutdown`
+    ///
+    /// A panic in the callback shuts down the stream and is resumed here.
+    pub fn start(&self, layout: ChannelLayout) -> Result<(), Error> {
+        if self.state.running.swap(true, Ordering::AcqRel) {
+            return Err(Error::WouldBlock);
+        }
+        let is_tx = layout.is_tx();
+        self.state.tx.store(is_tx, Ordering::Release);
+        self.state.reset(is_tx);
+        let err = unsafe { ffi::bladerf_stream(self.stream, layout.into_ffi()) };
+        let panic = unsafe { (*self.state.panic.get()).take() };
+        self.state.running.store(false, Ordering::Release);
+        if let Some(payload) = panic {
+            panic::resume_unwind(payload);
+        }
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+
+    /// Submit a buffer into a transfer slot left idle by the callback
+    /// returning `StreamAction::NoData`
+    ///
+    /// `fill` prepares the buffer (TX samples) before it is handed to libbladeRF.
+    /// Returns `Error::WouldBlock` without calling `fill` when no transfer slot
+    /// is idle. Must be called from another thread than the one running `start`.
+    pub fn submit<G>(&self, timeout: MilliSeconds, fill: G) -> Result<(), Error>
+    where
+        G: FnOnce(&mut [S]),
+    {
+        let timeout = timeout.0.try_into().map_err(|_| Error::Range)?;
+        let index = self.state.claim()?;
+        let mut claim = Claim {
+            state: &self.state,
+            index: Some(index),
+        };
+        let buffer = self.state.buffer(index);
+        fill(unsafe { self.state.buffer_mut(buffer, self.state.samples_per_buffer) });
+        let err = unsafe { ffi::bladerf_submit_stream_buffer(self.stream, buffer, timeout) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        claim.index = None;
+        Ok(())
+    }
+
+    /// Use the device while the stream exists, e.g. to retune from another
+    /// thread while `start` is running
+    pub fn with_device<R, G>(&self, f: G) -> R
+    where
+        G: FnOnce(&mut Device) -> R,
+    {
+        let mut dev = self.dev.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut dev)
+    }
+
+    pub fn num_buffers(&self) -> usize {
+        self.state.num_buffers
+    }
+
+    pub fn samples_per_buffer(&self) -> usize {
+        self.state.samples_per_buffer
+    }
+}
+
+impl<S, F> Drop for Stream<'_, S, F> {
+    fn drop(&mut self) {
+        if !self.stream.is_null() {
+            // Also frees the sample buffers
+            unsafe { ffi::bladerf_deinit_stream(self.stream) };
+        }
+    }
+}
+
+unsafe fn rx_callback<S, F>(
+    state: &StreamState<S, F>,
+    samples: *mut c_void,
+    num_samples: usize,
+) -> *mut c_void
+where
+    S: Sample,
+    F: FnMut(&mut [S]) -> StreamAction + Send,
+{
+    // samples was just filled, libbladeRF no longer owns it
+    let callback = &mut *state.callback.get();
+    let action = callback(state.buffer_mut(samples, num_samples));
+    let mut slots = state.lock_slots();
+    if let Some(index) = state.index_of(samples) {
+        slots.free.push_back(index);
+    }
+    match action {
+        StreamAction::Continue => match slots.free.pop_front() {
+            Some(index) => state.buffer(index),
+            None => {
+                slots.idle += 1;
+                STREAM_NO_DATA
+            }
+        },
+        StreamAction::NoData => {
+            slots.idle += 1;
+            STREAM_NO_DATA
+        }
+        StreamAction::Shutdown => std::ptr::null_mut(),
+    }
+}
+
+unsafe fn tx_callback<S, F>(state: &StreamState<S, F>, samples: *mut c_void) -> *mut c_void
+where
+    S: Sample,
+    F: FnMut(&mut [S]) -> StreamAction + Send,
+{
+    // samples is the buffer that was just transmitted (or NULL for the
+    // initial transfers), hand out a free one to fill
+    let claimed = {
+        let mut slots = state.lock_slots();
+        if let Some(index) = state.index_of(samples) {
+            slots.free.push_back(index);
+        }
+        let claimed = slots.free.pop_front();
+        if claimed.is_none() {
+            slots.idle += 1;
+        }
+        claimed
+    };
+    let index = match claimed {
+        Some(index) => index,
+        None => return STREAM_NO_DATA,
+    };
+    let buffer = state.buffer(index);
+    let callback = &mut *state.callback.get();
+    let action = callback(state.buffer_mut(buffer, state.samples_per_buffer));
+    if action == StreamAction::Continue {
+        return buffer;
+    }
+    let mut slots = state.lock_slots();
+    slots.free.push_front(index);
+    if action == StreamAction::NoData {
+        slots.idle += 1;
+        STREAM_NO_DATA
+    } else {
+        std::ptr::null_mut()
+    }
+}
+
+unsafe extern "C" fn stream_callback<S, F>(
+    _dev: *mut ffi::bladerf,
+    _stream: *mut ffi::bladerf_stream,
+    _meta: *mut ffi::bladerf_metadata,
+    samples: *mut c_void,
+    num_samples: usize,
+    user_data: *mut c_void,
+) -> *mut c_void
+where
+    S: Sample,
+    F: FnMut(&mut [S]) -> StreamAction + Send,
+{
+    let state = &*(user_data as *const StreamState<S, F>);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        if state.tx.load(Ordering::Acquire) {
+            tx_callback(state, samples)
+        } else {
+            rx_callback(state, samples, num_samples)
+        }
+    }));
+    match result {
+        Ok(buffer) => buffer,
+        Err(payload) => {
+            *state.panic.get() = Some(payload);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Format, Sc16Q11, Sc8Q7};
+
+    fn state<F>(callback: F, buffers: &mut [*mut c_void], tx: bool) -> StreamState<Sc16Q11, F> {
+        let state = StreamState {
+            callback: UnsafeCell::new(callback),
+            panic: UnsafeCell::new(None),
+            running: AtomicBool::new(true),
+            tx: AtomicBool::new(tx),
+            buffers: buffers.as_mut_ptr(),
+            num_buffers: buffers.len(),
+            num_transfers: 2,
+            samples_per_buffer: 4,
+            slots: Mutex::new(Slots {
+                free: VecDeque::new(),
+                idle: 0,
+            }),
+            _sample: PhantomData,
+        };
+        state.reset(tx);
+        state
+    }
+
+    unsafe fn call<F>(state: &StreamState<Sc16Q11, F>, samples: *mut c_void) -> *mut c_void
+    where
+        F: FnMut(&mut [Sc16Q11]) -> StreamAction + Send,
+    {
+        stream_callback::<Sc16Q11, F>(
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            samples,
+            4,
+            state as *const StreamState<Sc16Q11, F> as *mut c_void,
+        )
+    }
+
+    #[test]
+    fn rx_round_robin() {
+        let mut storage = [[Sc16Q11::default(); 4]; 3];
+        let mut buffers: Vec<*mut c_void> = storage
+            .iter_mut()
+            .map(|b| b.as_mut_ptr() as *mut c_void)
+            .collect();
+        let mut seen = 0;
+        let state = state(
+            |samples: &mut [Sc16Q11]| {
+                assert_eq!(samples.len(), 4);
+                seen += 1;
+                if seen == 3 {
+                    StreamAction::Shutdown
+                } else {
+                    StreamAction::Continue
+                }
+            },
+            &mut buffers,
+            false,
+        );
+        unsafe {
+            assert_eq!(call(&state, buffers[0]), buffers[2]);
+            assert_eq!(call(&state, buffers[1]), buffers[0]);
+            assert!(call(&state, buffers[2]).is_null());
+        }
+    }
+
+    #[test]
+    fn tx_fill_and_no_data() {
+        let mut storage = [[Sc16Q11::default(); 4]; 3];
+        let mut buffers: Vec<*mut c_void> = storage
+            .iter_mut()
+            .map(|b| b.as_mut_ptr() as *mut c_void)
+            .collect();
+        let mut count = 0;
+        let state = state(
+            |samples: &mut [Sc16Q11]| {
+                count += 1;
+                if count == 2 {
+                    return StreamAction::NoData;
+                }
+                samples
+                    .iter_mut()
+                    .for_each(|s| *s = Sc16Q11::new(count, -count));
+                StreamAction::Continue
+            },
+            &mut buffers,
+            true,
+        );
+        unsafe {
+            assert_eq!(call(&state, std::ptr::null_mut()), buffers[0]);
+            assert_eq!(call(&state, std::ptr::null_mut()), STREAM_NO_DATA);
+            // NoData does not consume a buffer
+            assert_eq!(call(&state, buffers[0]), buffers[1]);
+        }
+        assert_eq!(storage[0], [Sc16Q11::new(1, -1); 4]);
+        assert_eq!(storage[1], [Sc16Q11::new(3, -3); 4]);
+    }
+
+    #[test]
+    fn submit_needs_idle_slot() {
+        let mut storage = [[Sc16Q11::default(); 4]; 3];
+        let mut buffers: Vec<*mut c_void> = storage
+            .iter_mut()
+            .map(|b| b.as_mut_ptr() as *mut c_void)
+            .collect();
+        let mut no_data = false;
+        let handed_out = Mutex::new(Vec::new());
+        let state = state(
+            |samples: &mut [Sc16Q11]| {
+                let buffer = samples.as_mut_ptr() as *mut c_void;
+                handed_out.lock().unwrap().push(buffer as usize);
+                no_data = !no_data;
+                if no_data {
+                    StreamAction::NoData
+                } else {
+                    StreamAction::Continue
+                }
+            },
+            &mut buffers,
+            true,
+        );
+        // No slot is idle before the callback returned NoData
+        assert_eq!(state.claim(), Err(Error::WouldBlock));
+        unsafe {
+            // Slot 0 stays idle, slot 1 gets buffer 0
+            assert_eq!(call(&state, std::ptr::null_mut()), STREAM_NO_DATA);
+            assert_eq!(call(&state, std::ptr::null_mut()), buffers[0]);
+        }
+        // Only one slot is idle, a second early submit is refused
+        let claimed = state.claim().unwrap();
+        assert_eq!(state.claim(), Err(Error::WouldBlock));
+        assert_ne!(buffers[claimed], buffers[0]);
+        unsafe {
+            // Buffer 0 completes, the claimed buffer is not handed to the callback
+            assert_eq!(call(&state, buffers[0]), STREAM_NO_DATA);
+        }
+        let last = *handed_out.lock().unwrap().last().unwrap();
+        assert_ne!(last, buffers[claimed] as usize);
+        // A failed submit gives the claim back
+        state.release(claimed);
+        assert_eq!(state.claim(), Ok(claimed));
+    }
+
+    #[test]
+    fn submit_without_idle_slot() {
+        let mut dev = Device {
+            dev: std::ptr::null_mut(),
+            rx_format: None,
+            tx_format: None,
+        };
+        let mut storage = [[Sc16Q11::default(); 4]; 3];
+        let mut buffers: Vec<*mut c_void> = storage
+            .iter_mut()
+            .map(|b| b.as_mut_ptr() as *mut c_void)
+            .collect();
+        let stream = Stream {
+            stream: std::ptr::null_mut(),
+            state: Box::new(state(
+                |_: &mut [Sc16Q11]| StreamAction::Continue,
+                &mut buffers,
+                true,
+            )),
+            dev: Mutex::new(&mut dev),
+        };
+        let ret = stream.submit(MilliSeconds(10), |_| panic!("no slot is idle"));
+        assert_eq!(ret, Err(Error::WouldBlock));
+        assert!(stream.with_device(|dev| dev.dev.is_null()));
+    }
+
+    #[test]
+    fn callback_panic_shuts_down() {
+        let mut storage = [[Sc16Q11::default(); 4]; 3];
+        let mut buffers: Vec<*mut c_void> = storage
+            .iter_mut()
+            .map(|b| b.as_mut_ptr() as *mut c_void)
+            .collect();
+        let state = state(
+            |_: &mut [Sc16Q11]| -> StreamAction { panic!("boom") },
+            &mut buffers,
+            false,
+        );
+        let ret = unsafe { call(&state, buffers[0]) };
+        assert!(ret.is_null());
+        let payload = unsafe { (*state.panic.get()).take() }.unwrap();
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"boom"));
+    }
+
+    #[test]
+    fn sample_format_mismatch() {
+        let mut dev = Device {
+            dev: std::ptr::null_mut(),
+            rx_format: None,
+            tx_format: None,
+        };
+        let ret = Stream::new(
+            &mut dev,
+            Format::Sc8Q7,
+            4,
+            2048,
+            2,
+            |_: &mut [Sc16Q11]| StreamAction::Shutdown,
+        );
+        assert_eq!(ret.err(), Some(Error::SampleFormat));
+        let ret = Stream::new(
+            &mut dev,
+            Format::Sc16Q11Meta,
+            4,
+            1024,
+            2,
+            |_: &mut [Sc8Q7]| StreamAction::Shutdown,
+        );
+        assert_eq!(ret.err(), Some(Error::SampleFormat));
+    }
+}
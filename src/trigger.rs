@@ -0,0 +1,272 @@
+use crate::{ffi, Channel, Error};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum TriggerRole {
+    /// Trigger functionality is disabled
+    Disabled,
+    /// Drives the trigger signal, firing starts all armed devices
+    Master,
+    /// Waits for the master to fire the trigger signal
+    Slave,
+}
+
+impl TriggerRole {
+    pub(crate) fn into_ffi(self) -> ffi::bladerf_trigger_role {
+        use ffi::bladerf_trigger_role::*;
+        use TriggerRole::*;
+        match self {
+            Disabled => BLADERF_TRIGGER_ROLE_DISABLED,
+            Master => BLADERF_TRIGGER_ROLE_MASTER,
+            Slave => BLADERF_TRIGGER_ROLE_SLAVE,
+        }
+    }
+
+    pub(crate) fn from_ffi(role: ffi::bladerf_trigger_role) -> Result<Self, Error> {
+        use ffi::bladerf_trigger_role::*;
+        use TriggerRole::*;
+        match role {
+            BLADERF_TRIGGER_ROLE_DISABLED => Ok(Disabled),
+            BLADERF_TRIGGER_ROLE_MASTER => Ok(Master),
+            BLADERF_TRIGGER_ROLE_SLAVE => Ok(Slave),
+            BLADERF_TRIGGER_ROLE_INVALID => Err(Error::Invalid),
+        }
+    }
+}
+
+impl fmt::Display for TriggerRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use TriggerRole::*;
+        match self {
+            Disabled => write!(f, "Disabled"),
+            Master => write!(f, "Master"),
+            Slave => write!(f, "Slave"),
+        }
+    }
+}
+
+/// Signal line used for triggering
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum TriggerSignal {
+    /// J71 pin 4 (bladeRF1)
+    J71Pin4,
+    /// J51 pin 1 (bladeRF2)
+    J51Pin1,
+    /// Mini expansion header pin 1 (bladeRF2)
+    MiniExpPin1,
+    /// User-defined signals, for custom FPGA images
+    User0,
+    User1,
+    User2,
+    User3,
+    User4,
+    User5,
+    User6,
+    User7,
+}
+
+impl TriggerSignal {
+    pub(crate) fn into_ffi(self) -> ffi::bladerf_trigger_signal {
+        use ffi::bladerf_trigger_signal::*;
+        use TriggerSignal::*;
+        match self {
+            J71Pin4 => BLADERF_TRIGGER_J71_4,
+            J51Pin1 => BLADERF_TRIGGER_J51_1,
+            MiniExpPin1 => BLADERF_TRIGGER_MINI_EXP_1,
+            User0 => BLADERF_TRIGGER_USER_0,
+            User1 => BLADERF_TRIGGER_USER_1,
+            User2 => BLADERF_TRIGGER_USER_2,
+            User3 => BLADERF_TRIGGER_USER_3,
+            User4 => BLADERF_TRIGGER_USER_4,
+            User5 => BLADERF_TRIGGER_USER_5,
+            User6 => BLADERF_TRIGGER_USER_6,
+            User7 => BLADERF_TRIGGER_USER_7,
+        }
+    }
+
+    pub(crate) fn from_ffi(signal: ffi::bladerf_trigger_signal) -> Result<Self, Error> {
+        use ffi::bladerf_trigger_signal::*;
+        use TriggerSignal::*;
+        match signal {
+            BLADERF_TRIGGER_J71_4 => Ok(J71Pin4),
+            BLADERF_TRIGGER_J51_1 => Ok(J51Pin1),
+            BLADERF_TRIGGER_MINI_EXP_1 => Ok(MiniExpPin1),
+            BLADERF_TRIGGER_USER_0 => Ok(User0),
+            BLADERF_TRIGGER_USER_1 => Ok(User1),
+            BLADERF_TRIGGER_USER_2 => Ok(User2),
+            BLADERF_TRIGGER_USER_3 => Ok(User3),
+            BLADERF_TRIGGER_USER_4 => Ok(User4),
+            BLADERF_TRIGGER_USER_5 => Ok(User5),
+            BLADERF_TRIGGER_USER_6 => Ok(User6),
+            BLADERF_TRIGGER_USER_7 => Ok(User7),
+            BLADERF_TRIGGER_INVALID => Err(Error::Invalid),
+        }
+    }
+}
+
+impl fmt::Display for TriggerSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use TriggerSignal::*;
+        match self {
+            J71Pin4 => write!(f, "J71-4"),
+            J51Pin1 => write!(f, "J51-1"),
+            MiniExpPin1 => write!(f, "Mini expansion 1"),
+            User0 => write!(f, "User 0"),
+            User1 => write!(f, "User 1"),
+            User2 => write!(f, "User 2"),
+            User3 => write!(f, "User 3"),
+            User4 => write!(f, "User 4"),
+            User5 => write!(f, "User 5"),
+            User6 => write!(f, "User 6"),
+            User7 => write!(f, "User 7"),
+        }
+    }
+}
+
+/// Trigger configuration for a channel, created by `Device::trigger_init`
+///
+/// `trigger_init` leaves the role disabled, select one with `with_role`
+/// before arming.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Trigger {
+    channel: Channel,
+    role: TriggerRole,
+    signal: TriggerSignal,
+    options: u64,
+}
+
+impl Trigger {
+    pub(crate) fn from_ffi(
+        channel: Channel,
+        trigger: &ffi::bladerf_trigger,
+    ) -> Result<Self, Error> {
+        Ok(Trigger {
+            channel,
+            role: TriggerRole::from_ffi(trigger.role)?,
+            signal: TriggerSignal::from_ffi(trigger.signal)?,
+            options: trigger.options,
+        })
+    }
+
+    pub(crate) fn into_ffi(self) -> ffi::bladerf_trigger {
+        ffi::bladerf_trigger {
+            channel: self.channel.into_ffi(),
+            role: self.role.into_ffi(),
+            signal: self.signal.into_ffi(),
+            options: self.options,
+        }
+    }
+
+    pub fn with_role(mut self, role: TriggerRole) -> Self {
+        self.role = role;
+        self
+    }
+
+    pub fn channel(&self) -> Channel {
+        self.channel
+    }
+
+    pub fn role(&self) -> TriggerRole {
+        self.role
+    }
+
+    pub fn signal(&self) -> TriggerSignal {
+        self.signal
+    }
+}
+
+impl fmt::Display for Trigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} on {}", self.channel, self.role, self.signal)
+    }
+}
+
+/// Trigger state, as reported by `Device::trigger_state`
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct TriggerState {
+    pub armed: bool,
+    pub fired: bool,
+    /// Only meaningful for the master
+    pub fire_requested: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trigger_role_ffi() {
+        use ffi::bladerf_trigger_role::*;
+        use TriggerRole::*;
+        assert_eq!(Disabled.into_ffi(), BLADERF_TRIGGER_ROLE_DISABLED);
+        assert_eq!(Master.into_ffi(), BLADERF_TRIGGER_ROLE_MASTER);
+        assert_eq!(Slave.into_ffi(), BLADERF_TRIGGER_ROLE_SLAVE);
+        for role in [Disabled, Master, Slave] {
+            assert_eq!(TriggerRole::from_ffi(role.into_ffi()), Ok(role));
+        }
+        assert_eq!(
+            TriggerRole::from_ffi(BLADERF_TRIGGER_ROLE_INVALID),
+            Err(Error::Invalid)
+        );
+    }
+
+    #[test]
+    fn trigger_signal_ffi() {
+        use ffi::bladerf_trigger_signal::*;
+        use TriggerSignal::*;
+        assert_eq!(J71Pin4.into_ffi(), BLADERF_TRIGGER_J71_4);
+        assert_eq!(J51Pin1.into_ffi(), BLADERF_TRIGGER_J51_1);
+        assert_eq!(MiniExpPin1.into_ffi(), BLADERF_TRIGGER_MINI_EXP_1);
+        assert_eq!(User0.into_ffi(), BLADERF_TRIGGER_USER_0);
+        assert_eq!(User7.into_ffi(), BLADERF_TRIGGER_USER_7);
+        let signals = [
+            J71Pin4,
+            J51Pin1,
+            MiniExpPin1,
+            User0,
+            User1,
+            User2,
+            User3,
+            User4,
+            User5,
+            User6,
+            User7,
+        ];
+        for signal in signals {
+            assert_eq!(TriggerSignal::from_ffi(signal.into_ffi()), Ok(signal));
+        }
+        assert_eq!(
+            TriggerSignal::from_ffi(BLADERF_TRIGGER_INVALID),
+            Err(Error::Invalid)
+        );
+    }
+
+    #[test]
+    fn trigger_ffi() {
+        let raw = ffi::bladerf_trigger {
+            channel: Channel::Rx0.into_ffi(),
+            role: ffi::bladerf_trigger_role::BLADERF_TRIGGER_ROLE_DISABLED,
+            signal: ffi::bladerf_trigger_signal::BLADERF_TRIGGER_J51_1,
+            options: 0,
+        };
+        let trigger = Trigger::from_ffi(Channel::Rx0, &raw)
+            .unwrap()
+            .with_role(TriggerRole::Master);
+        assert_eq!(trigger.channel(), Channel::Rx0);
+        assert_eq!(trigger.role(), TriggerRole::Master);
+        assert_eq!(trigger.signal(), TriggerSignal::J51Pin1);
+        assert_eq!(trigger.to_string(), "Rx0 Master on J51-1");
+
+        let raw = trigger.into_ffi();
+        assert_eq!(raw.channel, Channel::Rx0.into_ffi());
+        assert_eq!(
+            raw.role,
+            ffi::bladerf_trigger_role::BLADERF_TRIGGER_ROLE_MASTER
+        );
+        assert_eq!(
+            raw.signal,
+            ffi::bladerf_trigger_signal::BLADERF_TRIGGER_J51_1
+        );
+    }
+}
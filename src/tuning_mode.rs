@@ -0,0 +1,61 @@
+use crate::{ffi, Error};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum TuningMode {
+    /// Tuning algorithms run on the host
+    Host,
+    /// Tuning algorithms run on the FPGA (NIOS II), faster retunes
+    Fpga,
+}
+
+impl TuningMode {
+    pub(crate) fn into_ffi(self) -> ffi::bladerf_tuning_mode {
+        use ffi::bladerf_tuning_mode::*;
+        use TuningMode::*;
+        match self {
+            Host => BLADERF_TUNING_MODE_HOST,
+            Fpga => BLADERF_TUNING_MODE_FPGA,
+        }
+    }
+
+    pub(crate) fn from_ffi(mode: ffi::bladerf_tuning_mode) -> Result<Self, Error> {
+        use ffi::bladerf_tuning_mode::*;
+        use TuningMode::*;
+        match mode {
+            BLADERF_TUNING_MODE_HOST => Ok(Host),
+            BLADERF_TUNING_MODE_FPGA => Ok(Fpga),
+            BLADERF_TUNING_MODE_INVALID => Err(Error::Invalid),
+        }
+    }
+}
+
+impl fmt::Display for TuningMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use TuningMode::*;
+        match self {
+            Host => write!(f, "Host"),
+            Fpga => write!(f, "FPGA"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tuning_mode_ffi() {
+        use ffi::bladerf_tuning_mode::*;
+        use TuningMode::*;
+        assert_eq!(Host.into_ffi(), BLADERF_TUNING_MODE_HOST);
+        assert_eq!(Fpga.into_ffi(), BLADERF_TUNING_MODE_FPGA);
+        assert_eq!(TuningMode::from_ffi(BLADERF_TUNING_MODE_HOST), Ok(Host));
+        assert_eq!(TuningMode::from_ffi(BLADERF_TUNING_MODE_FPGA), Ok(Fpga));
+        assert_eq!(
+            TuningMode::from_ffi(BLADERF_TUNING_MODE_INVALID),
+            Err(Error::Invalid)
+        );
+    }
+}
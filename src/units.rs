@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::num::ParseIntError;
 use std::str::FromStr;
+use std::time::Duration;
 
 /// Samples per second
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
@@ -23,6 +24,14 @@ pub struct MegaHertz(pub u64);
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
 pub struct MilliSeconds(pub u64);
 
+/// Gain in decibels
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub struct Gain(pub i32);
+
+/// Device timestamp, in samples at the current sample rate
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub struct Timestamp(pub u64);
+
 impl Sps {
     pub fn as_f64(self) -> f64 {
         self.0 as f64
@@ -53,6 +62,40 @@ impl MilliSeconds {
     }
 }
 
+impl Gain {
+    pub fn as_f64(self) -> f64 {
+        self.0 as f64
+    }
+}
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+impl Timestamp {
+    /// Number of samples spanning `duration` at `rate`, rounded down
+    pub fn from_duration(duration: Duration, rate: Sps) -> Self {
+        let samples = duration.as_nanos() * u128::from(rate.0) / NANOS_PER_SEC;
+        Timestamp(samples.try_into().unwrap_or(u64::MAX))
+    }
+
+    /// Time spanned by this many samples at `rate`, `None` if `rate` is zero
+    pub fn to_duration(self, rate: Sps) -> Option<Duration> {
+        if rate.0 == 0 {
+            return None;
+        }
+        let nanos = u128::from(self.0) * NANOS_PER_SEC / u128::from(rate.0);
+        let secs = (nanos / NANOS_PER_SEC).try_into().ok()?;
+        Some(Duration::new(secs, (nanos % NANOS_PER_SEC) as u32))
+    }
+
+    pub fn checked_add(self, samples: u64) -> Option<Self> {
+        self.0.checked_add(samples).map(Timestamp)
+    }
+
+    pub fn checked_sub(self, other: Timestamp) -> Option<u64> {
+        self.0.checked_sub(other.0)
+    }
+}
+
 pub trait UnitExt {
     /// Wrap in `Sps`
     fn sps(self) -> Sps;
@@ -132,6 +175,18 @@ impl From<u64> for MegaHertz {
     }
 }
 
+impl From<i32> for Gain {
+    fn from(val: i32) -> Self {
+        Gain(val)
+    }
+}
+
+impl From<u64> for Timestamp {
+    fn from(val: u64) -> Self {
+        Timestamp(val)
+    }
+}
+
 impl From<KiloHertz> for Hertz {
     fn from(value: KiloHertz) -> Self {
         Self(value.0 * 1_000)
@@ -210,6 +265,18 @@ impl fmt::Display for MegaHertz {
     }
 }
 
+impl fmt::Display for Gain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} dB", self.0)
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 pub const ONE_KHZ: Hertz = Hertz(1_000);
 pub const ONE_MHZ: Hertz = Hertz(1_000_000);
 pub const ONE_GHZ: Hertz = Hertz(1_000_000_000);
@@ -336,6 +403,27 @@ mod tests {
         assert_eq!(Hertz::from_str("12m"), Ok(12_u64.mhz().into()));
     }
 
+    #[test]
+    fn timestamp_duration() {
+        let rate = Sps(1_000_000);
+        let t = Timestamp::from_duration(Duration::from_millis(1500), rate);
+        assert_eq!(t, Timestamp(1_500_000));
+        assert_eq!(t.to_duration(rate), Some(Duration::from_millis(1500)));
+
+        let rate = Sps(30_720_000);
+        let t = Timestamp(30_720_000 * 3 + 3_072);
+        assert_eq!(t.to_duration(rate), Some(Duration::from_micros(3_000_100)));
+        assert_eq!(
+            Timestamp::from_duration(Duration::from_micros(3_000_100), rate),
+            t
+        );
+
+        assert_eq!(Timestamp(10).to_duration(Sps(0)), None);
+        assert_eq!(Timestamp(10).checked_add(5), Some(Timestamp(15)));
+        assert_eq!(Timestamp(10).checked_sub(Timestamp(4)), Some(6));
+        assert_eq!(Timestamp(u64::MAX).checked_add(1), None);
+    }
+
     #[test]
     fn sps_from_str() {
         assert_eq!(Sps::from_str("1"), Ok(Sps(1)));
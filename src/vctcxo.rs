@@ -0,0 +1,174 @@
+use crate::{ffi, Device, Error};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::mem::MaybeUninit;
+
+/// VCTCXO tamer mode, disciplines the VCTCXO from an external reference
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum TamerMode {
+    Disabled,
+    /// 1 PPS input
+    OnePps,
+    /// 10 MHz input
+    TenMhz,
+}
+
+impl TamerMode {
+    pub(crate) fn into_ffi(self) -> ffi::bladerf_vctcxo_tamer_mode {
+        use ffi::bladerf_vctcxo_tamer_mode::*;
+        use TamerMode::*;
+        match self {
+            Disabled => BLADERF_VCTCXO_TAMER_DISABLED,
+            OnePps => BLADERF_VCTCXO_TAMER_1_PPS,
+            TenMhz => BLADERF_VCTCXO_TAMER_10_MHZ,
+        }
+    }
+
+    pub(crate) fn from_ffi(mode: ffi::bladerf_vctcxo_tamer_mode) -> Result<Self, Error> {
+        use ffi::bladerf_vctcxo_tamer_mode::*;
+        use TamerMode::*;
+        match mode {
+            BLADERF_VCTCXO_TAMER_DISABLED => Ok(Disabled),
+            BLADERF_VCTCXO_TAMER_1_PPS => Ok(OnePps),
+            BLADERF_VCTCXO_TAMER_10_MHZ => Ok(TenMhz),
+            BLADERF_VCTCXO_TAMER_INVALID => Err(Error::Invalid),
+        }
+    }
+}
+
+impl fmt::Display for TamerMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use TamerMode::*;
+        match self {
+            Disabled => write!(f, "Disabled"),
+            OnePps => write!(f, "1 PPS"),
+            TenMhz => write!(f, "10 MHz"),
+        }
+    }
+}
+
+/// Access to the VCTCXO trim DAC and tamer, created by `Device::vctcxo`
+#[derive(Debug)]
+pub struct Vctcxo<'a> {
+    dev: &'a mut Device,
+}
+
+impl<'a> Vctcxo<'a> {
+    pub(crate) fn new(dev: &'a mut Device) -> Self {
+        Vctcxo { dev }
+    }
+
+    /// Factory calibrated trim value, stored in flash
+    pub fn factory_trim(&mut self) -> Result<u16, Error> {
+        let mut trim = 0;
+        let err = unsafe { ffi::bladerf_get_vctcxo_trim(self.dev.dev, &mut trim) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(trim)
+    }
+
+    /// Current value of the trim DAC
+    pub fn trim_dac(&mut self) -> Result<u16, Error> {
+        let mut val = 0;
+        let err = unsafe { ffi::bladerf_trim_dac_read(self.dev.dev, &mut val) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(val)
+    }
+
+    /// Write the trim DAC, the value is not persisted across power cycles
+    ///
+    /// Fails if the tamer is enabled, as it controls the DAC.
+    pub fn set_trim_dac(&mut self, val: u16) -> Result<(), Error> {
+        let err = unsafe { ffi::bladerf_trim_dac_write(self.dev.dev, val) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+
+    pub fn tamer_mode(&mut self) -> Result<TamerMode, Error> {
+        let mut mode = MaybeUninit::<ffi::bladerf_vctcxo_tamer_mode>::uninit();
+        let err = unsafe { ffi::bladerf_get_vctcxo_tamer_mode(self.dev.dev, mode.as_mut_ptr()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        let mode = unsafe { mode.assume_init() };
+        TamerMode::from_ffi(mode)
+    }
+
+    pub fn set_tamer_mode(&mut self, mode: TamerMode) -> Result<(), Error> {
+        let err = unsafe { ffi::bladerf_set_vctcxo_tamer_mode(self.dev.dev, mode.into_ffi()) };
+        if err != 0 {
+            return Err(Error::from(err));
+        }
+        Ok(())
+    }
+
+    /// Correct the trim DAC for a measured offset, see `trim_correction`
+    ///
+    /// Returns the value written to the DAC.
+    pub fn correct(&mut self, offset_ppm: f64, ppm_per_count: f64) -> Result<u16, Error> {
+        let trim = trim_correction(self.trim_dac()?, offset_ppm, ppm_per_count);
+        self.set_trim_dac(trim)?;
+        Ok(trim)
+    }
+}
+
+/// Compute a new trim DAC value that cancels a measured frequency offset
+///
+/// `offset_ppm` is the error of the reference, positive when it runs fast.
+/// A known tone at `f` received with a fast reference appears low, at
+/// `f_meas`, so `offset_ppm = (f - f_meas) / f * 1e6`.
+/// `ppm_per_count` is the tuning slope of the VCTCXO per DAC count, positive
+/// when a larger DAC value raises the frequency. It is board specific and
+/// can be estimated from two measurements at different trim values.
+///
+/// The result saturates at the DAC limits.
+pub fn trim_correction(trim: u16, offset_ppm: f64, ppm_per_count: f64) -> u16 {
+    if ppm_per_count == 0.0 || !ppm_per_count.is_finite() || !offset_ppm.is_finite() {
+        return trim;
+    }
+    let corrected = f64::from(trim) - offset_ppm / ppm_per_count;
+    corrected.round().clamp(0.0, f64::from(u16::MAX)) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tamer_mode_ffi() {
+        use ffi::bladerf_vctcxo_tamer_mode::*;
+        use TamerMode::*;
+        assert_eq!(Disabled.into_ffi(), BLADERF_VCTCXO_TAMER_DISABLED);
+        assert_eq!(OnePps.into_ffi(), BLADERF_VCTCXO_TAMER_1_PPS);
+        assert_eq!(TenMhz.into_ffi(), BLADERF_VCTCXO_TAMER_10_MHZ);
+        for mode in [Disabled, OnePps, TenMhz] {
+            assert_eq!(TamerMode::from_ffi(mode.into_ffi()), Ok(mode));
+        }
+        assert_eq!(
+            TamerMode::from_ffi(BLADERF_VCTCXO_TAMER_INVALID),
+            Err(Error::Invalid)
+        );
+    }
+
+    #[test]
+    fn trim_correction_math() {
+        // Running 0.5 ppm fast at 0.01 ppm/count, lower the DAC by 50 counts
+        assert_eq!(trim_correction(0x8000, 0.5, 0.01), 0x8000 - 50);
+        assert_eq!(trim_correction(0x8000, -0.5, 0.01), 0x8000 + 50);
+        // Negative slope inverts the correction
+        assert_eq!(trim_correction(0x8000, 0.5, -0.01), 0x8000 + 50);
+        // Rounds to the nearest count
+        assert_eq!(trim_correction(1000, 0.014, 0.01), 999);
+        // Saturates
+        assert_eq!(trim_correction(10, 1.0, 0.01), 0);
+        assert_eq!(trim_correction(u16::MAX - 10, -1.0, 0.01), u16::MAX);
+        // Degenerate inputs leave the trim unchanged
+        assert_eq!(trim_correction(1234, 1.0, 0.0), 1234);
+        assert_eq!(trim_correction(1234, f64::NAN, 0.01), 1234);
+    }
+}
@@ -0,0 +1,300 @@
+use crate::ffi;
+use serde::{Deserialize, Serialize};
+use std::ffi::CStr;
+use std::fmt;
+use std::mem::MaybeUninit;
+
+/// Firmware and FPGA versions a libbladeRF release expects
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct MinVersions {
+    /// The FX3 firmware is shared by bladeRF1 and bladeRF2
+    pub firmware: Version,
+    /// FPGA releases cover both bladeRF1 and bladeRF2
+    pub fpga: Version,
+}
+
+/// Versions shipped with each libbladeRF release, keyed by `(major, minor)`
+///
+/// libbladeRF does not export its compatibility tables, so they are
+/// mirrored here.
+const MIN_VERSIONS: &[((u16, u16), MinVersions)] = &[
+    (
+        (2, 2),
+        MinVersions {
+            firmware: Version::new(2, 3, 2),
+            fpga: Version::new(0, 11, 0),
+        },
+    ),
+    (
+        (2, 4),
+        MinVersions {
+            firmware: Version::new(2, 3, 2),
+            fpga: Version::new(0, 12, 0),
+        },
+    ),
+    (
+        (2, 5),
+        MinVersions {
+            firmware: Version::new(2, 4, 0),
+            fpga: Version::new(0, 15, 0),
+        },
+    ),
+];
+
+/// Firmware and FPGA versions expected by a libbladeRF release, `None` if
+/// the release is not known
+pub fn min_versions(lib: &Version) -> Option<&'static MinVersions> {
+    MIN_VERSIONS
+        .iter()
+        .find(|(key, _)| *key == (lib.major, lib.minor))
+        .map(|(_, min)| min)
+}
+
+/// Version of libbladeRF, the firmware or the FPGA
+///
+/// Ordered by major, minor and patch, `describe` only breaks ties.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub struct Version {
+    pub major: u16,
+    pub minor: u16,
+    pub patch: u16,
+    /// Version string with any additional suffix, e.g. `2.5.0-git-fe3304d7`
+    pub describe: String,
+}
+
+impl Version {
+    pub const fn new(major: u16, minor: u16, patch: u16) -> Self {
+        Version {
+            major,
+            minor,
+            patch,
+            describe: String::new(),
+        }
+    }
+
+    /// Copies the version, `describe` may point into memory owned by the device
+    pub(crate) fn from_ffi(version: &ffi::bladerf_version) -> Self {
+        let describe = if version.describe.is_null() {
+            String::new()
+        } else {
+            unsafe { CStr::from_ptr(version.describe) }
+                .to_string_lossy()
+                .into_owned()
+        };
+        Version {
+            major: version.major,
+            minor: version.minor,
+            patch: version.patch,
+            describe,
+        }
+    }
+
+    /// Returns true if this version is at least `major.minor.patch` of `other`,
+    /// ignoring `describe`
+    pub fn at_least(&self, other: &Version) -> bool {
+        (self.major, self.minor, self.patch) >= (other.major, other.minor, other.patch)
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Version of the linked libbladeRF
+pub fn lib_version() -> Version {
+    let mut version = MaybeUninit::<ffi::bladerf_version>::uninit();
+    unsafe { ffi::bladerf_version(version.as_mut_ptr()) };
+    let version = unsafe { version.assume_init() };
+    Version::from_ffi(&version)
+}
+
+/// Problem found by `Device::check_versions`
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum VersionWarning {
+    /// The linked libbladeRF release is not in the version table, the
+    /// firmware and FPGA could not be checked
+    UnknownLibrary {
+        found: Version,
+    },
+    Firmware {
+        found: Version,
+        required: Version,
+    },
+    Fpga {
+        found: Version,
+        required: Version,
+    },
+    /// The FPGA version could not be checked
+    FpgaNotLoaded,
+}
+
+impl VersionWarning {
+    pub(crate) fn check(lib: Version, firmware: Version, fpga: Option<Version>) -> Vec<Self> {
+        let min = match min_versions(&lib) {
+            Some(min) => min,
+            None => return vec![VersionWarning::UnknownLibrary { found: lib }],
+        };
+        let mut warnings = Vec::new();
+        if !firmware.at_least(&min.firmware) {
+            warnings.push(VersionWarning::Firmware {
+                found: firmware,
+                required: min.firmware.clone(),
+            });
+        }
+        match fpga {
+            Some(fpga) if !fpga.at_least(&min.fpga) => {
+                warnings.push(VersionWarning::Fpga {
+                    found: fpga,
+                    required: min.fpga.clone(),
+                });
+            }
+            Some(_) => (),
+            None => warnings.push(VersionWarning::FpgaNotLoaded),
+        }
+        warnings
+    }
+}
+
+impl fmt::Display for VersionWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VersionWarning::UnknownLibrary { found } => write!(
+                f,
+                "libbladeRF v{} is unknown, firmware and FPGA versions not checked",
+                found
+            ),
+            VersionWarning::Firmware { found, required } => write!(
+                f,
+                "Firmware v{} is older than v{}, please update",
+                found, required
+            ),
+            VersionWarning::Fpga { found, required } => write!(
+                f,
+                "FPGA v{} is older than v{}, please update",
+                found, required
+            ),
+            VersionWarning::FpgaNotLoaded => write!(f, "FPGA is not loaded, version not checked"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    #[test]
+    fn version_ffi() {
+        let describe = CString::new("2.5.0-git-fe3304d7").unwrap();
+        let raw = ffi::bladerf_version {
+            major: 2,
+            minor: 5,
+            patch: 0,
+            describe: describe.as_ptr(),
+        };
+        let v = Version::from_ffi(&raw);
+        assert_eq!((v.major, v.minor, v.patch), (2, 5, 0));
+        assert_eq!(v.describe, "2.5.0-git-fe3304d7");
+        assert_eq!(v.to_string(), "2.5.0");
+
+        let raw = ffi::bladerf_version {
+            describe: std::ptr::null(),
+            ..raw
+        };
+        assert_eq!(Version::from_ffi(&raw), Version::new(2, 5, 0));
+    }
+
+    #[test]
+    fn version_ord() {
+        assert!(Version::new(0, 15, 0) > Version::new(0, 9, 9));
+        assert!(Version::new(1, 0, 0) > Version::new(0, 15, 3));
+        assert!(Version::new(2, 4, 1) > Version::new(2, 4, 0));
+        let mut described = Version::new(2, 4, 0);
+        described.describe = "2.4.0-git".to_owned();
+        assert!(described.at_least(&Version::new(2, 4, 0)));
+        assert!(!Version::new(2, 3, 9).at_least(&Version::new(2, 4, 0)));
+
+        let json = serde_json::to_string(&described).unwrap();
+        let de: Version = serde_json::from_str(&json).unwrap();
+        assert_eq!(de, described);
+    }
+
+    #[test]
+    fn version_table() {
+        let min = min_versions(&Version::new(2, 5, 1)).unwrap();
+        assert_eq!(min.firmware, Version::new(2, 4, 0));
+        assert_eq!(min.fpga, Version::new(0, 15, 0));
+        assert!(min_versions(&Version::new(2, 2, 0)).is_some());
+        assert!(min_versions(&Version::new(1, 9, 0)).is_none());
+        assert!(min_versions(&Version::new(2, 6, 0)).is_none());
+    }
+
+    #[test]
+    fn version_warnings() {
+        let lib = Version::new(2, 5, 1);
+        let warnings = VersionWarning::check(
+            lib.clone(),
+            Version::new(2, 4, 0),
+            Some(Version::new(0, 15, 0)),
+        );
+        assert!(warnings.is_empty());
+        let warnings = VersionWarning::check(
+            lib.clone(),
+            Version::new(2, 3, 2),
+            Some(Version::new(0, 16, 0)),
+        );
+        assert_eq!(
+            warnings,
+            vec![VersionWarning::Firmware {
+                found: Version::new(2, 3, 2),
+                required: Version::new(2, 4, 0),
+            }]
+        );
+        assert_eq!(
+            warnings[0].to_string(),
+            "Firmware v2.3.2 is older than v2.4.0, please update"
+        );
+        let warnings = VersionWarning::check(
+            lib.clone(),
+            Version::new(2, 4, 0),
+            Some(Version::new(0, 11, 0)),
+        );
+        assert!(matches!(warnings[..], [VersionWarning::Fpga { .. }]));
+
+        // Firmware is still checked without an FPGA
+        let warnings = VersionWarning::check(lib, Version::new(2, 3, 0), None);
+        assert!(matches!(
+            warnings[..],
+            [
+                VersionWarning::Firmware { .. },
+                VersionWarning::FpgaNotLoaded
+            ]
+        ));
+
+        // Minimums follow the linked release
+        let warnings = VersionWarning::check(
+            Version::new(2, 2, 0),
+            Version::new(2, 3, 2),
+            Some(Version::new(0, 11, 0)),
+        );
+        assert!(warnings.is_empty());
+
+        let warnings = VersionWarning::check(
+            Version::new(2, 6, 0),
+            Version::new(2, 4, 0),
+            Some(Version::new(0, 15, 0)),
+        );
+        assert_eq!(
+            warnings,
+            vec![VersionWarning::UnknownLibrary {
+                found: Version::new(2, 6, 0)
+            }]
+        );
+        assert_eq!(
+            warnings[0].to_string(),
+            "libbladeRF v2.6.0 is unknown, firmware and FPGA versions not checked"
+        );
+    }
+}